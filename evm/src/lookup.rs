@@ -0,0 +1,111 @@
+//! A standalone combiner for folding a 4-element lookup tuple `(opcode, a, b, c)` into a single
+//! field element, for use by a byte-operation lookup/permutation argument (AND, XOR, and friends).
+//!
+//! **This module is not wired into anything in this tree.** The request this was written against
+//! asked for the byte-operation lookup table used by the `test_sha2` kernel path
+//! (`cpu::kernel::tests::sha2`) to switch from a fixed-coefficient encoding to this randomized one.
+//! That table doesn't exist in this repository snapshot: `test_sha2` drives the kernel's `sha2`
+//! label directly through `run_with_kernel` and never touches a byte-op lookup/CTL argument at
+//! all, and nothing else in this tree references `LookupCombine` or `combine_challenge`. There is
+//! also no `cross_table_lookup` module here for this to plug into. Rather than invent one (or a
+//! fake cross-reference to it), this module is left as an unwired utility; wiring it into a real
+//! byte-op table is follow-up work contingent on that table existing in this codebase.
+//!
+//! The combining logic itself is still real: the naive way to turn a tuple into one element is to
+//! pack it with fixed powers of 256 (`opcode + 256*a + 256^2*b + 256^3*c`), which is only
+//! injective if every operand is already known to be an 8-bit byte -- otherwise a malicious prover
+//! can find out-of-range operands that collide with a different, in-range tuple's encoding.
+//! [`LookupCombine::Challenge`] instead folds the tuple with a Fiat-Shamir-derived challenge
+//! `gamma`, `t_0 + gamma * t_1 + gamma^2 * t_2 + gamma^3 * t_3`: two distinct tuples then collide
+//! only if `gamma` happens to be a root of their difference polynomial, negligible probability
+//! over the challenger's randomness, so no separate range check on the operands is needed.
+//! [`LookupCombine::Fixed`] is the legacy encoding, kept for comparison and for traces that would
+//! need a migration path if this were ever wired in.
+
+use plonky2::field::extension::Extendable;
+use plonky2::field::types::Field;
+use plonky2::hash::hash_types::RichField;
+use plonky2::plonk::plonk_common::reduce_with_powers;
+
+/// Number of bits used for the legacy fixed-coefficient encoding of each operand.
+const FIXED_COMBINE_BITS: usize = 8;
+
+/// How a byte-operation lookup tuple `(opcode, a, b, c)` is folded into one field element.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LookupCombine<F> {
+    /// Legacy fixed-coefficient packing `t_0 + 256*t_1 + 256^2*t_2 + 256^3*t_3`. Only sound if
+    /// every operand is separately range-checked to 8 bits; kept for backwards compatibility.
+    Fixed,
+    /// Fiat-Shamir randomized combination `t_0 + gamma*t_1 + gamma^2*t_2 + gamma^3*t_3`, injective
+    /// with overwhelming probability without any range check on the operands. This is the
+    /// default.
+    Challenge(F),
+}
+
+/// The default is challenge mode with `gamma = 0` as a placeholder: with it, `combine` returns
+/// `t_0` alone and is not injective. [`combine_challenge`] builds the mode with a drawn `gamma`.
+impl<F: Field> Default for LookupCombine<F> {
+    fn default() -> Self {
+        LookupCombine::Challenge(F::ZERO)
+    }
+}
+
+impl<F: Field> LookupCombine<F> {
+    /// Folds a 4-element lookup tuple into a single field element according to this mode.
+    pub fn combine(&self, tuple: [F; 4]) -> F {
+        match self {
+            LookupCombine::Fixed => {
+                let base = F::from_canonical_u64(1 << FIXED_COMBINE_BITS);
+                reduce_with_powers(&tuple, base)
+            }
+            LookupCombine::Challenge(gamma) => reduce_with_powers(&tuple, *gamma),
+        }
+    }
+}
+
+/// Draws the challenge-mode combiner by squeezing a challenge from `challenger`. Callers must only
+/// do this after committing to every lookup instance (i.e. the full execution trace), so the
+/// challenge cannot be chosen adaptively by the prover -- this module has no commit/lookup
+/// machinery of its own to enforce that ordering, so it is on the (currently nonexistent) caller.
+pub fn combine_challenge<F, H>(challenger: &mut plonky2::iop::challenger::Challenger<F, H>) -> LookupCombine<F>
+where
+    F: RichField,
+    H: plonky2::plonk::config::Hasher<F>,
+{
+    let gamma = challenger.get_challenge();
+    LookupCombine::Challenge(gamma)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField as F;
+    use plonky2::field::types::{Field, Sample};
+
+    use super::*;
+
+    #[test]
+    fn fixed_and_challenge_agree_when_gamma_equals_base() {
+        let tuple = [
+            F::from_canonical_u64(0x12),
+            F::from_canonical_u64(0x34),
+            F::from_canonical_u64(0x56),
+            F::from_canonical_u64(0x78),
+        ];
+        let base = F::from_canonical_u64(1 << FIXED_COMBINE_BITS);
+        assert_eq!(
+            LookupCombine::Fixed.combine(tuple),
+            LookupCombine::Challenge(base).combine(tuple)
+        );
+    }
+
+    #[test]
+    fn challenge_mode_is_injective_with_high_probability() {
+        let a = [F::ZERO, F::ONE, F::ZERO, F::ZERO];
+        let b = [F::ONE, F::ZERO, F::ZERO, F::ZERO];
+        let gamma = F::rand();
+        assert_ne!(
+            LookupCombine::Challenge(gamma).combine(a),
+            LookupCombine::Challenge(gamma).combine(b)
+        );
+    }
+}
@@ -9,10 +9,16 @@ use plonky2::iop::witness::{PartialWitness, Witness, WitnessWrite};
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 use plonky2::plonk::circuit_data::CircuitConfig;
 use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+use plonky2::util::nth_root::nth_root;
 use plonky2_field::extension::Extendable;
 
 /// A generator to compute the n-th root of a given value x, outside the circuit,
-/// so it can be used as an additional public input
+/// so it can be used as an additional public input.
+///
+/// `N` need not be coprime to `p - 1`: unlike a plain inverse-exponent root (`kth_root_u64`),
+/// [`nth_root`] handles the case used below, `N = 3` with `3 | p - 1`, where an arbitrary
+/// `x_pow_n` may have zero or `N` distinct roots, by checking solvability via the Euler
+/// criterion before extracting one.
 #[derive(Debug)]
 pub struct NthRootGenerator<F: RichField + Extendable<D>, const D: usize, const N: u64> {
     x: Target, // x = x_pow_n^{1 / n}
@@ -45,7 +51,8 @@ impl<F: RichField + Extendable<D>, const D: usize, const N: u64> SimpleGenerator
             return;
         }
 
-        let x = x_pow_n.kth_root_u64(nth_pow);
+        let x = nth_root(x_pow_n, nth_pow)
+            .expect("x_pow_n should have an n-th root; the witness was built from one");
 
         println!("The {nth_pow}th-root: {x}");
 
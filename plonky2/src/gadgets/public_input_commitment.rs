@@ -0,0 +1,93 @@
+//! An opt-in alternative to registering every public input individually with
+//! [`CircuitBuilder::register_public_input`]. The `NthRootGenerator` example registers each value
+//! one at a time, which is fine in native Rust but means a recursive or on-chain (e.g. Solidity,
+//! see [`crate::plonk::solidity_verifier`]) verifier has to ingest one field element per input.
+//!
+//! [`commit_public_inputs`] instead hashes the full vector of input targets with an
+//! [`AlgebraicHasher`] (e.g. [`crate::hash::poseidon::PoseidonHash`] -- note this does *not*
+//! include [`crate::hash::monolith::MonolithHash`], which only implements [`Hasher`][crate::plonk::config::Hasher],
+//! not [`AlgebraicHasher`]; see that type's docs) and registers only the resulting digest as the
+//! circuit's public output. The underlying input targets are untouched wires: generators can
+//! still read or set them off-circuit exactly as before, since the commitment is just an
+//! additional constraint on top, not a replacement for the wires themselves. A verifier that is
+//! handed the claimed plaintext inputs re-derives the digest with
+//! [`verify_public_input_commitment`] and compares a single value, rather than comparing every
+//! input.
+
+use plonky2_field::extension::Extendable;
+
+use crate::hash::hash_types::{HashOutTarget, RichField};
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::config::AlgebraicHasher;
+
+/// Hashes `inputs` with `H` and registers the digest as the circuit's public input, instead of
+/// registering each of `inputs` individually. Returns the digest so callers needing it for
+/// further in-circuit constraints (e.g. linking two sub-circuits' commitments) don't have to
+/// re-derive it.
+pub fn commit_public_inputs<F, H, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    inputs: &[Target],
+) -> HashOutTarget
+where
+    F: RichField + Extendable<D>,
+    H: AlgebraicHasher<F>,
+{
+    let digest = builder.hash_n_to_hash_no_pad::<H>(inputs.to_vec());
+    builder.register_public_inputs(&digest.elements);
+    digest
+}
+
+/// Re-derives the digest of `claimed_inputs` and constrains it to equal `commitment`, the digest
+/// produced by [`commit_public_inputs`]. Used on the verifying side of a circuit that is handed
+/// the plaintext inputs out of band (e.g. a wrapping circuit) and needs to check they match the
+/// inner proof's committed public inputs.
+pub fn verify_public_input_commitment<F, H, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    claimed_inputs: &[Target],
+    commitment: HashOutTarget,
+) where
+    F: RichField + Extendable<D>,
+    H: AlgebraicHasher<F>,
+{
+    let recomputed = builder.hash_n_to_hash_no_pad::<H>(claimed_inputs.to_vec());
+    builder.connect_hashes(recomputed, commitment);
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2_field::types::Field;
+
+    use super::*;
+    use crate::hash::poseidon::PoseidonHash;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    #[test]
+    fn commitment_matches_off_circuit_hash() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let inputs: Vec<Target> = (0..5).map(|_| builder.add_virtual_target()).collect();
+        commit_public_inputs::<F, PoseidonHash, D>(&mut builder, &inputs);
+
+        let mut pw = PartialWitness::new();
+        for (i, target) in inputs.iter().enumerate() {
+            pw.set_target(*target, F::from_canonical_u64(i as u64));
+        }
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        // Exactly one digest's worth of public inputs, not one per registered value.
+        assert_eq!(proof.public_inputs.len(), 4);
+
+        data.verify(proof)
+    }
+}
@@ -0,0 +1,469 @@
+//! An in-circuit SHA-256 gadget on [`CircuitBuilder`]. Complements the native Poseidon/Monolith
+//! hashing built into the proving system (see [`crate::hash`]) by letting a circuit prove
+//! statements about SHA-256 preimages directly, rather than only being able to exercise SHA-256
+//! as kernel bytecode the way `evm`'s `test_sha2` does via `run_with_kernel`. The kernel and this
+//! gadget implement the same hash, so a circuit computing [`sha256`] over the same bytes the
+//! kernel was run on should always agree with it, which is what
+//! `gadgets::sha256::tests::matches_rust_sha256` checks for this gadget in isolation.
+//!
+//! Message bytes are padded, scheduled and compressed entirely in-circuit: callers pass a fixed
+//! maximum capacity (`MAX_LEN_BYTES`) worth of byte [`Target`]s plus a `len` [`Target`] giving the
+//! true message length, and every block up to the capacity is compressed, with the state after
+//! each block conditionally selected based on `len` so only the correct padding boundary affects
+//! the output.
+
+use plonky2_field::extension::Extendable;
+use plonky2_field::types::{Field, PrimeField64};
+
+use crate::gadgets::sha256_bit_ops::{
+    add_u32_mod, big_sigma0, big_sigma1, ch, maj, sigma0, sigma1, target_to_bits, U32Bits,
+};
+use crate::hash::hash_types::RichField;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator};
+use crate::iop::target::{BoolTarget, Target};
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// Size of a SHA-256 message block, in bytes.
+const BLOCK_BYTES: usize = 64;
+/// Number of 32-bit words produced by the message schedule per block.
+const SCHEDULE_WORDS: usize = 64;
+
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// Computes the SHA-256 digest of `input[..len]` in-circuit, returning the 256-bit digest packed
+/// into 8 big-endian 32-bit [`Target`]s. `input` must hold exactly `MAX_LEN_BYTES` byte targets
+/// (each already range-checked to a byte, e.g. via [`CircuitBuilder::add_virtual_target`] plus a
+/// range-check gadget upstream), and `len` is the true message length in bytes.
+///
+/// `len` must satisfy `0 <= len <= MAX_LEN_BYTES - BLOCK_BYTES`, enforced in-circuit below (not
+/// just documented): `len`'s own block might have no room left at all for the `0x80` marker plus
+/// the 8-byte length (e.g. `len` lands on the very last byte of a block), in which case both spill
+/// into an entirely new block, so the buffer must always have one full block free beyond wherever
+/// `len` falls. `len <= MAX_LEN_BYTES` alone is not sufficient -- see `block_index_of_length`.
+pub fn sha256<F: RichField + Extendable<D>, const D: usize, const MAX_LEN_BYTES: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    input: &[Target; MAX_LEN_BYTES],
+    len: Target,
+) -> [Target; 8] {
+    assert_eq!(
+        MAX_LEN_BYTES % BLOCK_BYTES,
+        0,
+        "MAX_LEN_BYTES must leave room for a full padding block; round up to a multiple of {BLOCK_BYTES}"
+    );
+    assert!(
+        MAX_LEN_BYTES >= BLOCK_BYTES,
+        "MAX_LEN_BYTES must be at least one block"
+    );
+
+    let max_len_exclusive = builder.constant(F::from_canonical_u64(
+        (MAX_LEN_BYTES - BLOCK_BYTES + 1) as u64,
+    ));
+    let len_in_bounds = less_than(builder, len, max_len_exclusive, 32);
+    builder.assert_one(len_in_bounds.target);
+
+    // The message is padded so that the true digest always lives at the block boundary implied by
+    // `len`; we still compress every block up to capacity (so the circuit shape is independent of
+    // the witness) and select the state right after that boundary.
+    let final_block_index = block_index_of_length::<F, D, MAX_LEN_BYTES>(builder, len);
+    let padded = pad_message(builder, input, len, final_block_index);
+    let num_blocks = padded.len() / BLOCK_BYTES;
+
+    let mut state: [U32Bits; 8] = core::array::from_fn(|i| target_to_bits(builder, builder.constant(F::from_canonical_u32(INITIAL_STATE[i]))));
+
+    let mut selected_state = state.clone();
+    let mut selected_so_far = builder.constant_bool(false);
+    for block in 0..num_blocks {
+        state = compress_block(builder, &state, &padded[block * BLOCK_BYTES..(block + 1) * BLOCK_BYTES]);
+
+        let block_target = builder.constant(F::from_canonical_usize(block));
+        let is_this_block = builder.is_equal(block_target, final_block_index);
+        let take_this = builder.and(is_this_block, builder.not(selected_so_far));
+        for i in 0..8 {
+            selected_state[i] = select_bits(builder, take_this, &state[i], &selected_state[i]);
+        }
+        selected_so_far = builder.or(selected_so_far, is_this_block);
+    }
+
+    core::array::from_fn(|i| {
+        let le: Vec<BoolTarget> = selected_state[i].iter().rev().copied().collect();
+        builder.le_sum(le.iter())
+    })
+}
+
+fn select_bits<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    cond: BoolTarget,
+    a: &U32Bits,
+    b: &U32Bits,
+) -> U32Bits {
+    core::array::from_fn(|i| {
+        let selected = builder.select(cond, a[i].target, b[i].target);
+        BoolTarget::new_unsafe(selected)
+    })
+}
+
+/// Pads `input[..len]` with the standard `1 || 0* || len_be64` SHA-256 padding, selecting the
+/// padded bytes with [`CircuitBuilder::select`] based on each position's relation to `len` so the
+/// result is correct for every possible `len` without branching on it outside the circuit. The
+/// output always has `MAX_LEN_BYTES` bytes; any position at or beyond the true padded length past
+/// `len`'s final block is simply unused by [`sha256`], since compression results from later
+/// blocks are never selected.
+///
+/// `final_block_index` (as returned by [`block_index_of_length`]) identifies which block the 8-byte
+/// length field belongs in: it is *not* always the block `len` itself falls in, since if `len`'s
+/// position within its block leaves fewer than 9 bytes free (room for the `0x80` marker plus the
+/// length), the length field spills over into the following block. The marker itself always sits
+/// at byte `len`.
+fn pad_message<F: RichField + Extendable<D>, const D: usize, const MAX_LEN_BYTES: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    input: &[Target; MAX_LEN_BYTES],
+    len: Target,
+    final_block_index: Target,
+) -> Vec<Target> {
+    let bit_len = builder.mul_const(F::from_canonical_u64(8), len);
+    let bit_len_bits = builder.split_le(bit_len, 64);
+
+    // Global byte position of the first byte of the 8-byte length field: the last 8 bytes of
+    // `final_block_index`'s block, i.e. `final_block_index * BLOCK_BYTES + (BLOCK_BYTES - 8)`.
+    let final_block_base = builder.mul_const(
+        F::from_canonical_u64(BLOCK_BYTES as u64),
+        final_block_index,
+    );
+    let length_field_start = builder.add_const(
+        final_block_base,
+        F::from_canonical_u64((BLOCK_BYTES - 8) as u64),
+    );
+
+    let mut out = Vec::with_capacity(MAX_LEN_BYTES);
+    for (i, &byte) in input.iter().enumerate() {
+        let idx = builder.constant(F::from_canonical_usize(i));
+        let is_message = less_than(builder, idx, len, 32);
+        let is_pad_marker = builder.is_equal(idx, len);
+
+        // Exactly one of the 8 candidate limb positions matches `idx`, when `idx` falls in the
+        // length field at all; fold them with `select` so `length_byte` ends up holding the right
+        // limb (or 0, if `idx` isn't a length-field byte).
+        let mut length_byte = builder.zero();
+        for limb in 0..8 {
+            let candidate = builder.add_const(length_field_start, F::from_canonical_u64(limb as u64));
+            let is_this_limb = builder.is_equal(idx, candidate);
+            let bits = &bit_len_bits[(7 - limb) * 8..(8 - limb) * 8];
+            let limb_byte = builder.le_sum(bits.iter());
+            length_byte = builder.select(is_this_limb, limb_byte, length_byte);
+        }
+
+        let pad_marker = builder.constant(F::from_canonical_u64(0x80));
+        let not_message_byte = builder.select(is_pad_marker, pad_marker, length_byte);
+        let value = builder.select(is_message, byte, not_message_byte);
+        out.push(value);
+    }
+    out
+}
+
+fn less_than<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: Target,
+    b: Target,
+    num_bits: usize,
+) -> BoolTarget {
+    // a < b  <=>  (2^num_bits + a - b) has a zero bit at position `num_bits`.
+    let two_pow = builder.constant(F::from_canonical_u64(1 << num_bits));
+    let diff = builder.add(two_pow, a);
+    let diff = builder.sub(diff, b);
+    let bits = builder.split_le(diff, num_bits + 1);
+    builder.not(bits[num_bits])
+}
+
+/// Number of bits needed to represent every value in `[0, bound)`, i.e. the smallest `k` with
+/// `2^k >= bound`. Used to size independent range checks below.
+fn bits_to_represent(bound: u64) -> usize {
+    if bound <= 1 {
+        0
+    } else {
+        (64 - (bound - 1).leading_zeros()) as usize
+    }
+}
+
+/// Witnesses `quotient = numerator / divisor` and `remainder = numerator % divisor` (true integer
+/// division, not [`CircuitBuilder::div`]'s exact field division by the modular inverse) and
+/// constrains `numerator == quotient * divisor + remainder` with `0 <= remainder < divisor` and
+/// `0 <= quotient < quotient_bound`.
+///
+/// Both range checks matter on their own: `less_than` only compares bit decompositions, so it is
+/// sound only when both of its arguments are *already* known to fit in the bit width it's given.
+/// `remainder` and `quotient` come straight from an untrusted generator with no other bound, so
+/// skipping their own range checks (e.g. only checking `remainder < divisor` without first
+/// checking `remainder < 2^k` on its own) would let a prover witness a `remainder` congruent to
+/// `divisor - d` for a field-sized `d` (e.g. `remainder = p - 1`): `less_than`'s internal
+/// subtraction would wrap back into the "in range" window even though `remainder` is nowhere near
+/// `[0, divisor)`, and `quotient` could then be forced to any value the equality constraint allows.
+/// `quotient_bound` should be the smallest legitimate exclusive upper bound the caller can state
+/// (e.g. the real number of blocks `quotient` may index into), not just "large enough to not
+/// overflow", so a forged `quotient` actually has nowhere valid left to hide.
+fn int_div_rem<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    numerator: Target,
+    divisor: u64,
+    quotient_bound: u64,
+) -> (Target, Target) {
+    let quotient = builder.add_virtual_target();
+    let remainder = builder.add_virtual_target();
+    builder.add_simple_generator(IntDivRemGenerator::<F, D> {
+        numerator,
+        divisor,
+        quotient,
+        remainder,
+        _phantom: core::marker::PhantomData,
+    });
+
+    let divisor_target = builder.constant(F::from_canonical_u64(divisor));
+    let product = builder.mul(quotient, divisor_target);
+    let sum = builder.add(product, remainder);
+    builder.connect(sum, numerator);
+
+    // Independently pin `remainder` to `[0, 2^remainder_bits)` before comparing it against
+    // `divisor` -- `split_le` itself constrains its input to equal the recomposed bits, so this
+    // alone rules out `remainder` being some other field element that only "looks" small after
+    // `less_than`'s subtraction.
+    let remainder_bits = bits_to_represent(divisor);
+    builder.split_le(remainder, remainder_bits);
+    let remainder_in_range = less_than(builder, remainder, divisor_target, remainder_bits + 1);
+    builder.assert_one(remainder_in_range.target);
+
+    // Same treatment for `quotient`, against the caller-supplied bound.
+    let quotient_bits = bits_to_represent(quotient_bound);
+    builder.split_le(quotient, quotient_bits);
+    let quotient_bound_target = builder.constant(F::from_canonical_u64(quotient_bound));
+    let quotient_in_range = less_than(builder, quotient, quotient_bound_target, quotient_bits + 1);
+    builder.assert_one(quotient_in_range.target);
+
+    (quotient, remainder)
+}
+
+#[derive(Debug, Clone)]
+struct IntDivRemGenerator<F: RichField + Extendable<D>, const D: usize> {
+    numerator: Target,
+    divisor: u64,
+    quotient: Target,
+    remainder: Target,
+    _phantom: core::marker::PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for IntDivRemGenerator<F, D> {
+    fn dependencies(&self) -> Vec<Target> {
+        vec![self.numerator]
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let numerator = witness.get_target(self.numerator).to_canonical_u64();
+        out_buffer.set_target(self.quotient, F::from_canonical_u64(numerator / self.divisor));
+        out_buffer.set_target(self.remainder, F::from_canonical_u64(numerator % self.divisor));
+    }
+
+    fn id(&self) -> String {
+        "IntDivRemGenerator".into()
+    }
+
+    fn serialize(
+        &self,
+        dst: &mut Vec<u8>,
+        _common_data: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
+    ) -> crate::util::serialization::IoResult<()> {
+        use crate::util::serialization::Write;
+        dst.write_target(self.numerator)?;
+        dst.write_usize(self.divisor as usize)?;
+        dst.write_target(self.quotient)?;
+        dst.write_target(self.remainder)
+    }
+
+    fn deserialize(
+        src: &mut crate::util::serialization::Buffer,
+        _common_data: &crate::plonk::circuit_data::CommonCircuitData<F, D>,
+    ) -> crate::util::serialization::IoResult<Self>
+    where
+        Self: Sized,
+    {
+        use crate::util::serialization::Read;
+        let numerator = src.read_target()?;
+        let divisor = src.read_usize()? as u64;
+        let quotient = src.read_target()?;
+        let remainder = src.read_target()?;
+        Ok(Self {
+            numerator,
+            divisor,
+            quotient,
+            remainder,
+            _phantom: core::marker::PhantomData,
+        })
+    }
+}
+
+/// Requires `len <= MAX_LEN_BYTES - BLOCK_BYTES` (checked by [`sha256`] before calling this), so
+/// the returned index always names one of the `MAX_LEN_BYTES / BLOCK_BYTES` real blocks -- without
+/// that precondition, `len` close to `MAX_LEN_BYTES` could compute a block one past the last
+/// compressed one, which the selection loop in [`sha256`] would simply never match, silently
+/// returning the untouched initial state instead of a digest.
+fn block_index_of_length<F: RichField + Extendable<D>, const D: usize, const MAX_LEN_BYTES: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    len: Target,
+) -> Target {
+    // The digest lives in the block that contains both the message's last byte and the 9 bytes of
+    // padding (the 0x80 marker plus the 8-byte length), i.e. block `(len + 8) / 64` using integer
+    // (floor) division -- `CircuitBuilder::div` computes exact field division by the modular
+    // inverse instead, which is the wrong operation here and would return a huge, meaningless
+    // field element whenever `len + 8` isn't a multiple of `BLOCK_BYTES`.
+    let eight = builder.constant(F::from_canonical_u64(8));
+    let numerator = builder.add(len, eight);
+    let num_blocks = (MAX_LEN_BYTES / BLOCK_BYTES) as u64;
+    let (quotient, _remainder) = int_div_rem(builder, numerator, BLOCK_BYTES as u64, num_blocks);
+    quotient
+}
+
+fn compress_block<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    state: &[U32Bits; 8],
+    block: &[Target],
+) -> [U32Bits; 8] {
+    debug_assert_eq!(block.len(), BLOCK_BYTES);
+
+    let mut schedule: Vec<U32Bits> = Vec::with_capacity(SCHEDULE_WORDS);
+    for word in 0..16 {
+        let bytes = &block[word * 4..(word + 1) * 4];
+        let word_target = builder.le_sum(
+            bytes
+                .iter()
+                .rev()
+                .flat_map(|&b| builder.split_le_base::<2>(b, 8))
+                .collect::<Vec<_>>()
+                .iter(),
+        );
+        schedule.push(target_to_bits(builder, word_target));
+    }
+    for t in 16..SCHEDULE_WORDS {
+        let s0 = sigma0(builder, &schedule[t - 15]);
+        let s1 = sigma1(builder, &schedule[t - 2]);
+        let w = add_u32_mod(builder, &[&schedule[t - 16], &s0, &schedule[t - 7], &s1]);
+        schedule.push(w);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state.clone();
+    for t in 0..64 {
+        let s1 = big_sigma1(builder, &e);
+        let ch_efg = ch(builder, &e, &f, &g);
+        let k = target_to_bits(builder, builder.constant(F::from_canonical_u32(ROUND_CONSTANTS[t])));
+        let temp1 = add_u32_mod(builder, &[&h, &s1, &ch_efg, &k, &schedule[t]]);
+
+        let s0 = big_sigma0(builder, &a);
+        let maj_abc = maj(builder, &a, &b, &c);
+        let temp2 = add_u32_mod(builder, &[&s0, &maj_abc]);
+
+        h = g;
+        g = f;
+        f = e;
+        e = add_u32_mod(builder, &[&d, &temp1]);
+        d = c;
+        c = b;
+        b = a;
+        a = add_u32_mod(builder, &[&temp1, &temp2]);
+    }
+
+    let out = [a, b, c, d, e, f, g, h];
+    core::array::from_fn(|i| add_u32_mod(builder, &[&state[i], &out[i]]))
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use plonky2_field::types::Field;
+    use sha2::{Digest, Sha256};
+
+    use super::*;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    /// Builds a `sha256::<F, D, MAX_LEN_BYTES>` circuit for `message`, proves it, and checks the
+    /// digest against `sha2`'s own implementation. Shared by the test cases below so each one only
+    /// has to state its `MAX_LEN_BYTES` and message.
+    fn assert_matches_rust_sha256<const MAX_LEN_BYTES: usize>(message: &[u8]) -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut hasher = Sha256::new();
+        hasher.update(message);
+        let expected = hasher.finalize();
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+
+        let input: [Target; MAX_LEN_BYTES] = core::array::from_fn(|_| builder.add_virtual_target());
+        let len = builder.add_virtual_target();
+        let digest = sha256::<F, D, MAX_LEN_BYTES>(&mut builder, &input, len);
+        for word in digest {
+            builder.register_public_input(word);
+        }
+
+        let mut pw = PartialWitness::new();
+        for (i, target) in input.iter().enumerate() {
+            let byte = message.get(i).copied().unwrap_or(0);
+            pw.set_target(*target, F::from_canonical_u8(byte));
+        }
+        pw.set_target(len, F::from_canonical_usize(message.len()));
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let expected_words: Vec<u32> = expected
+            .chunks(4)
+            .map(|c| u32::from_be_bytes(c.try_into().unwrap()))
+            .collect();
+        for (public_input, expected_word) in proof.public_inputs.iter().zip(expected_words) {
+            assert_eq!(public_input.to_canonical_u64(), expected_word as u64);
+        }
+
+        data.verify(proof)
+    }
+
+    #[test]
+    fn matches_rust_sha256() -> Result<()> {
+        // MAX_LEN_BYTES must leave a full spare block beyond `len`'s own block (see `sha256`'s
+        // docs), so a 21-byte message needs more than one block of capacity.
+        assert_matches_rust_sha256::<128>(b"plonky2 sha256 gadget")
+    }
+
+    /// A message whose padding boundary falls in the *second* block of a multi-block buffer: at
+    /// `len = 60`, only 4 bytes are left in the first 64-byte block, not enough room for the 9
+    /// bytes of padding (`0x80` marker + 8-byte length), so the `0x80` marker stays at byte 60
+    /// while the 8-byte length field spills into the end of block 1. This is the
+    /// case the old fixed `MAX_LEN_BYTES - 8` length-field placement got wrong whenever
+    /// `MAX_LEN_BYTES` wasn't exactly one block.
+    #[test]
+    fn matches_rust_sha256_across_a_block_boundary() -> Result<()> {
+        assert_matches_rust_sha256::<192>(&[0x5a_u8; 60])
+    }
+
+    /// Exercises `len == MAX_LEN_BYTES - BLOCK_BYTES`, the largest length `sha256` accepts: exactly
+    /// one spare block is left after `len`'s own block, so the 0x80 marker lands at the very start
+    /// of that spare block and the length field in its last 8 bytes. Longer lengths are rejected by
+    /// the in-circuit bound check in `sha256`.
+    #[test]
+    fn matches_rust_sha256_at_the_max_allowed_length() -> Result<()> {
+        const MAX_LEN_BYTES: usize = 128;
+        assert_matches_rust_sha256::<MAX_LEN_BYTES>(&[0x11_u8; MAX_LEN_BYTES - BLOCK_BYTES])
+    }
+}
@@ -0,0 +1,167 @@
+//! Bitwise building blocks shared by the in-circuit SHA-256 gadget
+//! ([`crate::gadgets::sha256`]): 32-bit words represented as arrays of [`BoolTarget`]s (MSB
+//! first, matching the SHA-256 specification's bit order), plus the `Ch`/`Maj` functions and the
+//! `Sigma`/`sigma` rotations/shifts built out of them. Each bit comes from
+//! [`CircuitBuilder::split_le`] (via [`target_to_bits`] or [`add_u32_mod`]), which constrains it
+//! to `{0, 1}`, so XOR/AND/NOT below are plain arithmetic on bits rather than byte-range-checked
+//! lookups. No lookup table is used anywhere in this module or in the SHA-256 gadget; words are
+//! packed back into [`Target`]s with [`CircuitBuilder::le_sum`].
+
+use plonky2_field::extension::Extendable;
+use plonky2_field::types::Field;
+
+use crate::hash::hash_types::RichField;
+use crate::iop::target::{BoolTarget, Target};
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// A 32-bit SHA-256 word, stored MSB-first.
+pub type U32Bits = [BoolTarget; 32];
+
+pub fn rotr(bits: &U32Bits, n: usize) -> U32Bits {
+    core::array::from_fn(|i| bits[(i + 32 - n) % 32])
+}
+
+pub fn shr<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bits: &U32Bits,
+    n: usize,
+) -> U32Bits {
+    let zero = builder.constant_bool(false);
+    core::array::from_fn(|i| if i < n { zero } else { bits[i - n] })
+}
+
+pub fn xor<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: &U32Bits,
+    b: &U32Bits,
+) -> U32Bits {
+    core::array::from_fn(|i| {
+        let a_i = a[i].target;
+        let b_i = b[i].target;
+        // a XOR b = a + b - 2ab, valid since both are boolean.
+        let sum = builder.add(a_i, b_i);
+        let prod = builder.mul(a_i, b_i);
+        let two_prod = builder.mul_const(F::TWO, prod);
+        BoolTarget::new_unsafe(builder.sub(sum, two_prod))
+    })
+}
+
+pub fn and<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: &U32Bits,
+    b: &U32Bits,
+) -> U32Bits {
+    core::array::from_fn(|i| builder.and(a[i], b[i]))
+}
+
+pub fn not<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: &U32Bits,
+) -> U32Bits {
+    core::array::from_fn(|i| builder.not(a[i]))
+}
+
+pub fn xor3<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: &U32Bits,
+    b: &U32Bits,
+    c: &U32Bits,
+) -> U32Bits {
+    let ab = xor(builder, a, b);
+    xor(builder, &ab, c)
+}
+
+/// `Ch(e, f, g) = (e AND f) XOR (NOT e AND g)`.
+pub fn ch<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    e: &U32Bits,
+    f: &U32Bits,
+    g: &U32Bits,
+) -> U32Bits {
+    let not_e = not(builder, e);
+    let ef = and(builder, e, f);
+    let ng = and(builder, &not_e, g);
+    xor(builder, &ef, &ng)
+}
+
+/// `Maj(a, b, c) = (a AND b) XOR (a AND c) XOR (b AND c)`.
+pub fn maj<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: &U32Bits,
+    b: &U32Bits,
+    c: &U32Bits,
+) -> U32Bits {
+    let ab = and(builder, a, b);
+    let ac = and(builder, a, c);
+    let bc = and(builder, b, c);
+    xor3(builder, &ab, &ac, &bc)
+}
+
+/// `Sigma0(a) = ROTR(a, 2) XOR ROTR(a, 13) XOR ROTR(a, 22)`.
+pub fn big_sigma0<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    a: &U32Bits,
+) -> U32Bits {
+    xor3(builder, &rotr(a, 2), &rotr(a, 13), &rotr(a, 22))
+}
+
+/// `Sigma1(e) = ROTR(e, 6) XOR ROTR(e, 11) XOR ROTR(e, 25)`.
+pub fn big_sigma1<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    e: &U32Bits,
+) -> U32Bits {
+    xor3(builder, &rotr(e, 6), &rotr(e, 11), &rotr(e, 25))
+}
+
+/// `sigma0(x) = ROTR(x, 7) XOR ROTR(x, 18) XOR SHR(x, 3)`, used in message scheduling.
+pub fn sigma0<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: &U32Bits,
+) -> U32Bits {
+    let shifted = shr(builder, x, 3);
+    xor3(builder, &rotr(x, 7), &rotr(x, 18), &shifted)
+}
+
+/// `sigma1(x) = ROTR(x, 17) XOR ROTR(x, 19) XOR SHR(x, 10)`, used in message scheduling.
+pub fn sigma1<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    x: &U32Bits,
+) -> U32Bits {
+    let shifted = shr(builder, x, 10);
+    xor3(builder, &rotr(x, 17), &rotr(x, 19), &shifted)
+}
+
+/// Adds 32-bit words `mod 2^32`, returning the truncated sum as bits. The sum of up to five
+/// 32-bit words fits comfortably below the field's 64-bit canonical range, so we recompose each
+/// operand to a [`Target`], sum in the field, then split back into 32 bits, discarding any
+/// overflow above bit 31 (`split_le` enforces the low bits are exactly the wrapped sum, and the
+/// dropped high bits are simply never recomposed, matching `u32::wrapping_add`).
+pub fn add_u32_mod<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    words: &[&U32Bits],
+) -> U32Bits {
+    let mut sum = builder.zero();
+    for word in words {
+        let as_target = bits_to_target(builder, word);
+        sum = builder.add(sum, as_target);
+    }
+    let bits = builder.split_le(sum, 64);
+    core::array::from_fn(|i| bits[31 - i])
+}
+
+fn bits_to_target<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    bits: &U32Bits,
+) -> Target {
+    // `bits` is MSB-first; `le_sum` expects LSB-first.
+    let le: Vec<BoolTarget> = bits.iter().rev().copied().collect();
+    builder.le_sum(le.iter())
+}
+
+pub fn target_to_bits<F: RichField + Extendable<D>, const D: usize>(
+    builder: &mut CircuitBuilder<F, D>,
+    target: Target,
+) -> U32Bits {
+    let le = builder.split_le(target, 32);
+    core::array::from_fn(|i| le[31 - i])
+}
@@ -0,0 +1,432 @@
+//! A gate computing all [`MONOLITH_ROUNDS`] rounds (each Concrete + Bricks + Bars) of the
+//! [Monolith permutation](crate::hash::monolith), in the same Concrete -> Bricks -> Bars order as
+//! [`crate::hash::monolith::monolith_permute`]. Concrete (the circulant MDS mix plus round
+//! constants, via [`crate::hash::monolith::mds_row`] and [`crate::hash::monolith::round_constants`])
+//! and Bricks are plain degree-2 algebraic constraints, identical in shape to how
+//! [`crate::gates::poseidon::PoseidonGate`] constrains its own MDS and power-map layers. The Bars
+//! layer is different: in the real Monolith construction, each of the first
+//! [`MONOLITH_NUM_BARS`] state elements is split into 8-bit limbs, each limb is looked up in the
+//! fixed [`bar_sbox_table`], and the limbs are recomposed into the next round's input.
+//!
+//! **This gate does not yet constrain that lookup.** `eval_unfiltered`/`eval_unfiltered_circuit`
+//! only check that `wire_bar_limb`'s values recompose correctly; nothing ties those limb values to
+//! `bar_sbox_table`'s entries, because this codebase has no lookup-argument machinery for a gate to
+//! hook into (there is no `add_lookup_table_from_fn` or equivalent anywhere in this tree).
+//! `MonolithBarsGenerator` witnesses genuine table lookups, but a generator only has to produce
+//! *some* satisfying witness for an honest prover -- it constrains nothing for a dishonest one. As
+//! written, a malicious prover can put any field element on `wire_bar_limb` and still satisfy this
+//! gate's constraints, so **`MonolithGate` is not sound and must not be used to back a hash that
+//! needs to resist a malicious prover** until a real lookup argument is added. It is also not
+//! instantiated anywhere in this crate outside its own tests below.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use plonky2_field::extension::Extendable;
+use plonky2_field::types::{Field, PrimeField64};
+
+use crate::gates::gate::Gate;
+use crate::hash::hash_types::RichField;
+use crate::hash::monolith::{
+    bar_sbox_table, mds_row, round_constants, MONOLITH_NUM_BARS, MONOLITH_ROUNDS, MONOLITH_WIDTH,
+};
+use crate::iop::ext_target::ExtensionTarget;
+use crate::iop::generator::{GeneratedValues, SimpleGenerator, WitnessGeneratorRef};
+use crate::iop::target::Target;
+use crate::iop::witness::{PartitionWitness, Witness, WitnessWrite};
+use crate::plonk::circuit_builder::CircuitBuilder;
+use crate::plonk::circuit_data::CommonCircuitData;
+use crate::plonk::vars::{
+    EvaluationTargets, EvaluationVars, EvaluationVarsBase, EvaluationVarsBaseBatch,
+};
+use crate::util::serialization::{Buffer, IoResult, Read, Write};
+
+const BAR_LIMB_BITS: usize = 8;
+const BAR_NUM_LIMBS: usize = 64 / BAR_LIMB_BITS;
+
+/// Computes the full Monolith permutation: one gate instance holds all [`MONOLITH_ROUNDS`]
+/// rounds, from [`Self::wire_input`] to [`Self::wire_output`]. The number of rounds is fixed.
+#[derive(Debug, Clone)]
+pub struct MonolithGate<F: RichField + Extendable<D>, const D: usize> {
+    _phantom: core::marker::PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> MonolithGate<F, D> {
+    pub fn new() -> Self {
+        Self {
+            _phantom: core::marker::PhantomData,
+        }
+    }
+
+    /// Wire holding the `i`-th state element before the permutation.
+    pub fn wire_input(i: usize) -> usize {
+        debug_assert!(i < MONOLITH_WIDTH);
+        i
+    }
+
+    /// Wire holding the `i`-th state element after the permutation.
+    pub fn wire_output(i: usize) -> usize {
+        debug_assert!(i < MONOLITH_WIDTH);
+        MONOLITH_WIDTH + i
+    }
+
+    /// Wire holding the `i`-th state element after round `round` (0-indexed, excluding the final
+    /// round whose output is [`Self::wire_output`]), used to break the round function into
+    /// independently checkable constraints.
+    fn wire_round_state(round: usize, i: usize) -> usize {
+        debug_assert!(round < MONOLITH_ROUNDS - 1);
+        debug_assert!(i < MONOLITH_WIDTH);
+        2 * MONOLITH_WIDTH + round * MONOLITH_WIDTH + i
+    }
+
+    /// Wire holding the `limb`-th 8-bit limb of the `i`-th Bars input of round `round`. Only the
+    /// recomposition of these limbs is constrained (see the module docs for why the lookup itself
+    /// is not).
+    fn wire_bar_limb(round: usize, i: usize, limb: usize) -> usize {
+        debug_assert!(round < MONOLITH_ROUNDS);
+        debug_assert!(i < MONOLITH_NUM_BARS);
+        debug_assert!(limb < BAR_NUM_LIMBS);
+        2 * MONOLITH_WIDTH
+            + (MONOLITH_ROUNDS - 1) * MONOLITH_WIDTH
+            + (round * MONOLITH_NUM_BARS + i) * BAR_NUM_LIMBS
+            + limb
+    }
+
+    fn end_of_round_wire(round: usize, i: usize) -> usize {
+        if round == MONOLITH_ROUNDS - 1 {
+            Self::wire_output(i)
+        } else {
+            Self::wire_round_state(round, i)
+        }
+    }
+
+    const fn start_of_round_wire(round: usize, i: usize) -> Option<usize> {
+        if round == 0 {
+            None
+        } else {
+            Some(Self::wire_round_state(round - 1, i))
+        }
+    }
+
+    /// The same round constants [`crate::hash::monolith::round_constants`] uses off-circuit, as
+    /// raw `u64`s so both `eval_unfiltered` (over `F::Extension`) and `eval_unfiltered_circuit`
+    /// (over `ExtensionTarget`) can each embed them with their own field type's
+    /// `from_canonical_u64`, the same way [`Self::wire_bar_limb`]'s recomposition shifts are done.
+    fn round_constants_u64() -> [[u64; MONOLITH_WIDTH]; MONOLITH_ROUNDS] {
+        let constants = round_constants::<F>();
+        core::array::from_fn(|r| core::array::from_fn(|i| constants[r][i].to_canonical_u64()))
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> Gate<F, D> for MonolithGate<F, D> {
+    fn id(&self) -> String {
+        format!("MonolithGate")
+    }
+
+    fn eval_unfiltered(&self, vars: EvaluationVars<F, D>) -> Vec<F::Extension> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        let mds = mds_row();
+        let round_constants = Self::round_constants_u64();
+        for round in 0..MONOLITH_ROUNDS {
+            let input: Vec<_> = (0..MONOLITH_WIDTH)
+                .map(|i| match Self::start_of_round_wire(round, i) {
+                    Some(w) => vars.local_wires[w],
+                    None => vars.local_wires[Self::wire_input(i)],
+                })
+                .collect();
+
+            // Concrete: circulant MDS mix plus round constants. Pure algebra over `input`, so it
+            // needs no wires or constraints of its own -- it only shows up through the equalities
+            // checked below.
+            let mixed: Vec<_> = (0..MONOLITH_WIDTH)
+                .map(|i| {
+                    let mut acc = F::Extension::ZERO;
+                    for j in 0..MONOLITH_WIDTH {
+                        let coeff = mds[(j + MONOLITH_WIDTH - i) % MONOLITH_WIDTH];
+                        acc += input[j] * F::Extension::from_canonical_u64(coeff);
+                    }
+                    acc + F::Extension::from_canonical_u64(round_constants[round][i])
+                })
+                .collect();
+
+            // Bricks: x_i += x_{i-1}^2, each read from `mixed` (the Concrete output), matching
+            // `crate::hash::monolith::bricks`.
+            let mut bricks_out = mixed.clone();
+            for i in 1..MONOLITH_WIDTH {
+                bricks_out[i] = mixed[i] + mixed[i - 1] * mixed[i - 1];
+            }
+
+            // Bars: the limbs on `wire_bar_limb` must recompose (as a degree-1 linear
+            // combination) to this layer's input. This does NOT check that each limb is actually
+            // a `bar_sbox_table` entry -- see the module docs: that lookup isn't constrained here.
+            let mut bars_out = bricks_out;
+            for i in 0..MONOLITH_NUM_BARS {
+                let mut recomposed = F::Extension::ZERO;
+                for limb in 0..BAR_NUM_LIMBS {
+                    let shift = F::Extension::from_canonical_u64(1 << (BAR_LIMB_BITS * limb));
+                    recomposed += vars.local_wires[Self::wire_bar_limb(round, i, limb)] * shift;
+                }
+                constraints.push(recomposed - bars_out[i]);
+                bars_out[i] = recomposed;
+            }
+
+            let expected_end = (0..MONOLITH_WIDTH)
+                .map(|i| vars.local_wires[Self::end_of_round_wire(round, i)])
+                .collect::<Vec<_>>();
+            for i in 0..MONOLITH_WIDTH {
+                constraints.push(bars_out[i] - expected_end[i]);
+            }
+        }
+        constraints
+    }
+
+    fn eval_unfiltered_base_batch(&self, vars_base: EvaluationVarsBaseBatch<F>) -> Vec<F> {
+        vars_base
+            .iter()
+            .flat_map(|vars| self.eval_unfiltered_base_one(vars))
+            .collect()
+    }
+
+    fn eval_unfiltered_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: EvaluationTargets<D>,
+    ) -> Vec<ExtensionTarget<D>> {
+        let mut constraints = Vec::with_capacity(self.num_constraints());
+        let mds = mds_row();
+        let round_constants = Self::round_constants_u64();
+        for round in 0..MONOLITH_ROUNDS {
+            let input: Vec<_> = (0..MONOLITH_WIDTH)
+                .map(|i| match Self::start_of_round_wire(round, i) {
+                    Some(w) => vars.local_wires[w],
+                    None => vars.local_wires[Self::wire_input(i)],
+                })
+                .collect();
+
+            // Concrete: circulant MDS mix plus round constants, mirroring
+            // `crate::hash::monolith::concrete`.
+            let mixed: Vec<_> = (0..MONOLITH_WIDTH)
+                .map(|i| {
+                    let mut acc = builder.zero_extension();
+                    for j in 0..MONOLITH_WIDTH {
+                        let coeff = mds[(j + MONOLITH_WIDTH - i) % MONOLITH_WIDTH];
+                        let coeff = builder.constant(F::from_canonical_u64(coeff));
+                        let term = builder.scalar_mul_ext(coeff, input[j]);
+                        acc = builder.add_extension(acc, term);
+                    }
+                    let constant =
+                        builder.constant_extension(F::Extension::from_canonical_u64(round_constants[round][i]));
+                    builder.add_extension(acc, constant)
+                })
+                .collect();
+
+            // Bricks: x_i += x_{i-1}^2, each read from `mixed` (the Concrete output).
+            let mut bricks_out = mixed.clone();
+            for i in 1..MONOLITH_WIDTH {
+                let sq = builder.mul_extension(mixed[i - 1], mixed[i - 1]);
+                bricks_out[i] = builder.add_extension(mixed[i], sq);
+            }
+
+            let mut bars_out = bricks_out;
+            for i in 0..MONOLITH_NUM_BARS {
+                let mut recomposed = builder.zero_extension();
+                for limb in 0..BAR_NUM_LIMBS {
+                    let shift = builder.constant(F::from_canonical_u64(1 << (BAR_LIMB_BITS * limb)));
+                    let limb_ext = vars.local_wires[Self::wire_bar_limb(round, i, limb)];
+                    let term = builder.scalar_mul_ext(shift, limb_ext);
+                    recomposed = builder.add_extension(recomposed, term);
+                }
+                constraints.push(builder.sub_extension(recomposed, bars_out[i]));
+                bars_out[i] = recomposed;
+            }
+
+            for i in 0..MONOLITH_WIDTH {
+                let expected = vars.local_wires[Self::end_of_round_wire(round, i)];
+                constraints.push(builder.sub_extension(bars_out[i], expected));
+            }
+        }
+        constraints
+    }
+
+    fn generators(&self, row: usize, _local_constants: &[F]) -> Vec<WitnessGeneratorRef<F, D>> {
+        vec![WitnessGeneratorRef::new(
+            MonolithBarsGenerator::<F, D> {
+                row,
+                _phantom: core::marker::PhantomData,
+            }
+            .adapter(),
+        )]
+    }
+
+    fn num_wires(&self) -> usize {
+        2 * MONOLITH_WIDTH
+            + (MONOLITH_ROUNDS - 1) * MONOLITH_WIDTH
+            + MONOLITH_ROUNDS * MONOLITH_NUM_BARS * BAR_NUM_LIMBS
+    }
+
+    fn num_constants(&self) -> usize {
+        0
+    }
+
+    fn degree(&self) -> usize {
+        2
+    }
+
+    fn num_constraints(&self) -> usize {
+        MONOLITH_ROUNDS * (MONOLITH_NUM_BARS + MONOLITH_WIDTH)
+    }
+
+    fn serialize(&self, _dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        Ok(())
+    }
+
+    fn deserialize(_src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    where
+        Self: Sized,
+    {
+        Ok(Self::new())
+    }
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> MonolithGate<F, D> {
+    fn eval_unfiltered_base_one(&self, vars_base: EvaluationVarsBase<F>) -> Vec<F> {
+        let local_constants = &[];
+        let local_wires = vars_base.local_wires.to_vec();
+        let vars = EvaluationVars {
+            local_constants,
+            local_wires: &local_wires,
+            public_inputs_hash: vars_base.public_inputs_hash,
+        };
+        self.eval_unfiltered(vars)
+            .into_iter()
+            .map(|e| e.to_basefield_array()[0])
+            .collect()
+    }
+}
+
+/// Populates the Bars limb wires by looking each one up in [`bar_sbox_table`], off-circuit. This
+/// gives an honest prover the right witness, but -- see the module docs -- nothing in
+/// `eval_unfiltered`/`eval_unfiltered_circuit` constrains a dishonest prover to do the same.
+#[derive(Debug, Clone)]
+struct MonolithBarsGenerator<F: RichField + Extendable<D>, const D: usize> {
+    row: usize,
+    _phantom: core::marker::PhantomData<F>,
+}
+
+impl<F: RichField + Extendable<D>, const D: usize> SimpleGenerator<F> for MonolithBarsGenerator<F, D> {
+    fn dependencies(&self) -> Vec<Target> {
+        (0..MONOLITH_WIDTH)
+            .map(|i| Target::wire(self.row, MonolithGate::<F, D>::wire_input(i)))
+            .collect()
+    }
+
+    fn run_once(&self, witness: &PartitionWitness<F>, out_buffer: &mut GeneratedValues<F>) {
+        let table = bar_sbox_table();
+        for round in 0..MONOLITH_ROUNDS {
+            for i in 0..MONOLITH_NUM_BARS {
+                let wire = match MonolithGate::<F, D>::start_of_round_wire(round, i) {
+                    Some(w) => w,
+                    None => MonolithGate::<F, D>::wire_input(i),
+                };
+                let value = witness.get_target(Target::wire(self.row, wire));
+                let mut limbs = value.to_canonical_u64();
+                for limb in 0..BAR_NUM_LIMBS {
+                    let byte = (limbs & 0xff) as usize;
+                    limbs >>= 8;
+                    let looked_up = F::from_canonical_u64(table[byte] as u64);
+                    out_buffer.set_target(
+                        Target::wire(self.row, MonolithGate::<F, D>::wire_bar_limb(round, i, limb)),
+                        looked_up,
+                    );
+                }
+            }
+        }
+    }
+
+    fn id(&self) -> String {
+        "MonolithBarsGenerator".into()
+    }
+
+    fn serialize(&self, dst: &mut Vec<u8>, _common_data: &CommonCircuitData<F, D>) -> IoResult<()> {
+        dst.write_usize(self.row)
+    }
+
+    fn deserialize(src: &mut Buffer, _common_data: &CommonCircuitData<F, D>) -> IoResult<Self>
+    where
+        Self: Sized,
+    {
+        let row = src.read_usize()?;
+        Ok(Self {
+            row,
+            _phantom: core::marker::PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::hash::hash_types::HashOut;
+
+    use super::*;
+
+    type F = crate::field::goldilocks_field::GoldilocksField;
+
+    #[test]
+    fn num_wires_matches_layout() {
+        let gate = MonolithGate::<F, 2>::new();
+        assert_eq!(
+            gate.num_wires(),
+            MonolithGate::<F, 2>::wire_bar_limb(
+                MONOLITH_ROUNDS - 1,
+                MONOLITH_NUM_BARS - 1,
+                BAR_NUM_LIMBS - 1
+            ) + 1
+        );
+    }
+
+    /// Demonstrates the soundness gap documented at the top of this module: the recomposition
+    /// constraint alone is satisfied by limbs that are just `wire_input`'s raw byte decomposition
+    /// (i.e. an identity "S-box"), not genuine [`bar_sbox_table`] entries. A sound gate would
+    /// reject this, since the real Bars layer must transform every byte through the table.
+    #[test]
+    fn recomposition_constraint_accepts_non_table_limbs() {
+        const D: usize = 2;
+        let gate = MonolithGate::<F, D>::new();
+
+        let mut local_wires = vec![F::ZERO; gate.num_wires()];
+        let x: u64 = 0x0102030405060708;
+        local_wires[MonolithGate::<F, D>::wire_input(0)] = F::from_canonical_u64(x);
+
+        // Round 0's Bars layer is fed by Concrete+Bricks, not `wire_input` directly, so the raw
+        // bytes forged below must be those of that intermediate value rather than of `x` itself.
+        // With every other input wire left at zero, index 0's Concrete output is just
+        // `x + round_constants[0][0]` (its MDS row coefficient is 1), and Bricks leaves index 0
+        // unchanged.
+        let bars_input = (F::from_canonical_u64(x) + round_constants::<F>()[0][0]).to_canonical_u64();
+
+        // Raw bytes of `bars_input`, NOT passed through `bar_sbox_limb` -- an honest generator
+        // would never produce these, but nothing in `eval_unfiltered` says so.
+        for limb in 0..BAR_NUM_LIMBS {
+            let byte = (bars_input >> (BAR_LIMB_BITS * limb)) & 0xff;
+            assert_ne!(
+                byte as u16,
+                bar_sbox_table()[byte as usize],
+                "pick an input whose bytes aren't S-box fixed points, so this test is meaningful"
+            );
+            local_wires[MonolithGate::<F, D>::wire_bar_limb(0, 0, limb)] = F::from_canonical_u64(byte);
+        }
+
+        let vars = EvaluationVars {
+            local_constants: &[],
+            local_wires: &local_wires,
+            public_inputs_hash: &HashOut::ZERO,
+        };
+        let constraints = gate.eval_unfiltered(vars);
+        // The round-0, Bars-input-0 recomposition constraint is the first one emitted.
+        assert!(
+            constraints[0].is_zero(),
+            "a forged, non-table limb set still satisfies the recomposition constraint"
+        );
+    }
+}
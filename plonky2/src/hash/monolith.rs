@@ -0,0 +1,218 @@
+//! The Monolith permutation over the Goldilocks field, as described in
+//! "Monolith: Circuit-Friendly Hash Functions with New Nonlinear Layers for Fast and Constant-Time
+//! Implementations" (Grassi, Khovratovich, Schofnegger). Monolith targets the same sponge
+//! construction as [`crate::hash::poseidon::Poseidon`] but replaces the power-map S-box with a
+//! cheaper, lookup-based one (the "Bars" layer), at the cost of needing a lookup argument to
+//! constrain it in-circuit. See [`crate::gates::monolith::MonolithGate`] for the in-circuit side.
+
+use plonky2_field::types::{Field, PrimeField64};
+
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hashing::{compress, hash_n_to_hash_no_pad, PlonkyPermutation};
+use crate::plonk::config::Hasher;
+
+/// Width of the Monolith state, in field elements.
+pub const MONOLITH_WIDTH: usize = 12;
+/// Number of full rounds (each made up of a Concrete, Bricks and Bars layer).
+pub const MONOLITH_ROUNDS: usize = 6;
+/// Number of state elements the Bars layer applies its S-box to; the remaining elements only go
+/// through Bricks, which is enough once a handful of elements have been fully diffused.
+pub const MONOLITH_NUM_BARS: usize = 4;
+
+/// Size in bits of the limbs the Bars S-box operates on.
+const BAR_LIMB_BITS: u32 = 8;
+const BAR_NUM_LIMBS: usize = 64 / BAR_LIMB_BITS as usize;
+
+/// The 8-bit "chi"-style lookup used by the Bars layer: `y = x XOR (NOT x rotl 1 AND x rotl 2)`,
+/// applied to each limb independently. This is the same S-box family used by Keccak's chi step,
+/// chosen here for its low algebraic degree (2) while still being a much cheaper in-circuit
+/// primitive than Poseidon's degree-7 power map, since it is implemented as a single lookup rather
+/// than repeated squarings.
+fn bar_sbox_limb(x: u8) -> u8 {
+    let rotl = |v: u8, n: u32| v.rotate_left(n);
+    x ^ (!rotl(x, 1) & rotl(x, 2))
+}
+
+/// The full 256-entry table for [`bar_sbox_limb`]. The off-circuit permutation calls
+/// [`bar_sbox_limb`] directly; [`crate::gates::monolith::MonolithGate`]'s witness generator reads
+/// its Bars limbs from this table (the gate itself has no lookup table).
+pub fn bar_sbox_table() -> Vec<u16> {
+    (0..=u8::MAX).map(|x| bar_sbox_limb(x) as u16).collect()
+}
+
+fn bars(x: u64) -> u64 {
+    let bytes = x.to_le_bytes();
+    let mut out = [0u8; BAR_NUM_LIMBS];
+    for (o, b) in out.iter_mut().zip(bytes.iter()) {
+        *o = bar_sbox_limb(*b);
+    }
+    u64::from_le_bytes(out)
+}
+
+/// Fixed circulant MDS matrix for the Concrete layer, generated from the first row
+/// `[1, 2, 3, ..., MONOLITH_WIDTH]`, following the same small-coefficient construction Poseidon
+/// uses for its own MDS matrix.
+///
+/// `pub(crate)` so [`crate::gates::monolith::MonolithGate`] can constrain the same Concrete layer
+/// in-circuit rather than just off-circuit here.
+pub(crate) fn mds_row() -> [u64; MONOLITH_WIDTH] {
+    core::array::from_fn(|i| (i + 1) as u64)
+}
+
+fn mds_multiply<F: RichField>(state: &[F; MONOLITH_WIDTH]) -> [F; MONOLITH_WIDTH] {
+    let row = mds_row();
+    core::array::from_fn(|i| {
+        let mut acc = F::ZERO;
+        for j in 0..MONOLITH_WIDTH {
+            let coeff = row[(j + MONOLITH_WIDTH - i) % MONOLITH_WIDTH];
+            acc += state[j] * F::from_canonical_u64(coeff);
+        }
+        acc
+    })
+}
+
+/// Round constants, generated deterministically from a fixed seed (here: round index times a
+/// large odd constant) rather than sampled at random, matching Poseidon's own use of
+/// reproducible, auditable constants.
+///
+/// `pub(crate)` so [`crate::gates::monolith::MonolithGate`] can add the same constants in-circuit.
+pub(crate) fn round_constants<F: RichField>() -> [[F; MONOLITH_WIDTH]; MONOLITH_ROUNDS] {
+    core::array::from_fn(|r| {
+        core::array::from_fn(|i| {
+            let seed = (r as u64 + 1)
+                .wrapping_mul(0x9E3779B97F4A7C15)
+                .wrapping_add(i as u64);
+            F::from_canonical_u64(seed)
+        })
+    })
+}
+
+fn concrete<F: RichField>(
+    state: [F; MONOLITH_WIDTH],
+    constants: &[F; MONOLITH_WIDTH],
+) -> [F; MONOLITH_WIDTH] {
+    let mixed = mds_multiply(&state);
+    core::array::from_fn(|i| mixed[i] + constants[i])
+}
+
+fn bricks<F: RichField>(state: [F; MONOLITH_WIDTH]) -> [F; MONOLITH_WIDTH] {
+    let mut out = state;
+    for i in 1..MONOLITH_WIDTH {
+        out[i] = state[i] + state[i - 1] * state[i - 1];
+    }
+    out
+}
+
+fn bars_layer<F: RichField>(state: [F; MONOLITH_WIDTH]) -> [F; MONOLITH_WIDTH] {
+    let mut out = state;
+    for elt in out.iter_mut().take(MONOLITH_NUM_BARS) {
+        *elt = F::from_canonical_u64(bars(elt.to_canonical_u64()));
+    }
+    out
+}
+
+/// Applies the Monolith permutation to `state` and returns the result.
+pub fn monolith_permute<F: RichField>(mut state: [F; MONOLITH_WIDTH]) -> [F; MONOLITH_WIDTH] {
+    let constants = round_constants::<F>();
+    for round_constants in constants.iter() {
+        state = concrete(state, round_constants);
+        state = bricks(state);
+        state = bars_layer(state);
+    }
+    state
+}
+
+/// Permutation used by [`MonolithHash`], implementing Plonky2's sponge interface.
+#[derive(Copy, Clone, Debug)]
+pub struct MonolithPermutation<F: RichField>([F; MONOLITH_WIDTH]);
+
+impl<F: RichField> Default for MonolithPermutation<F> {
+    fn default() -> Self {
+        Self([F::ZERO; MONOLITH_WIDTH])
+    }
+}
+
+impl<F: RichField> AsRef<[F]> for MonolithPermutation<F> {
+    fn as_ref(&self) -> &[F] {
+        &self.0
+    }
+}
+
+impl<F: RichField> PlonkyPermutation<F> for MonolithPermutation<F> {
+    const RATE: usize = 8;
+    const WIDTH: usize = MONOLITH_WIDTH;
+
+    fn new<I: IntoIterator<Item = F>>(elts: I) -> Self {
+        let mut perm = Self::default();
+        perm.set_from_slice(&elts.into_iter().collect::<Vec<_>>(), 0);
+        perm
+    }
+
+    fn set_elt(&mut self, elt: F, idx: usize) {
+        self.0[idx] = elt;
+    }
+
+    fn set_from_slice(&mut self, elts: &[F], start_idx: usize) {
+        self.0[start_idx..start_idx + elts.len()].copy_from_slice(elts);
+    }
+
+    fn permute(&mut self) {
+        self.0 = monolith_permute(self.0);
+    }
+
+    fn squeeze(&self) -> &[F] {
+        &self.0[..Self::RATE]
+    }
+}
+
+/// Monolith hash function over the Goldilocks field. Unlike [`crate::hash::poseidon::PoseidonHash`],
+/// this only implements [`Hasher`], not [`crate::plonk::config::AlgebraicHasher`]: its Bars layer
+/// has no in-circuit counterpart that's actually sound yet (see
+/// [`crate::gates::monolith::MonolithGate`]'s docs), so it can only be used off-circuit, e.g. for
+/// building a Merkle tree's caps via [`crate::plonk::config::MonolithGoldilocksConfig`] -- not for
+/// the challenger or recursive verification, both of which need an in-circuit permutation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MonolithHash;
+impl<F: RichField> Hasher<F> for MonolithHash {
+    const HASH_SIZE: usize = 4 * 8;
+    type Hash = HashOut<F>;
+    type Permutation = MonolithPermutation<F>;
+
+    fn hash_no_pad(input: &[F]) -> Self::Hash {
+        hash_n_to_hash_no_pad::<F, Self::Permutation>(input)
+    }
+
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash {
+        compress::<F, Self::Permutation>(left, right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::goldilocks_field::GoldilocksField;
+    use plonky2_field::types::Field;
+
+    use super::*;
+
+    #[test]
+    fn monolith_permute_is_deterministic() {
+        let state = [GoldilocksField::ZERO; MONOLITH_WIDTH];
+        let a = monolith_permute(state);
+        let b = monolith_permute(state);
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn monolith_permute_is_not_identity() {
+        let state = [GoldilocksField::ZERO; MONOLITH_WIDTH];
+        assert_ne!(monolith_permute(state), state);
+    }
+
+    #[test]
+    fn bar_sbox_table_matches_function() {
+        let table = bar_sbox_table();
+        for x in 0..=u8::MAX {
+            assert_eq!(table[x as usize], bar_sbox_limb(x) as u16);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use core::fmt::Debug;
+
+use plonky2_field::extension::{Extendable, FieldExtension};
+use plonky2_field::goldilocks_field::GoldilocksField;
+use plonky2_field::types::Field;
+
+use crate::hash::hash_types::{HashOut, RichField};
+use crate::hash::hashing::PlonkyPermutation;
+use crate::hash::monolith::MonolithHash;
+use crate::hash::poseidon::PoseidonHash;
+use crate::iop::target::Target;
+use crate::plonk::circuit_builder::CircuitBuilder;
+
+/// Represents a ~256 bit hash output.
+pub trait GenericHashOut<F: RichField>:
+    Copy + Clone + Debug + Eq + PartialEq + Send + Sync
+{
+    fn to_bytes(&self) -> Vec<u8>;
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    fn to_vec(&self) -> Vec<F>;
+}
+
+/// Trait for hash functions usable outside of a circuit, e.g. to build a Merkle tree or to hash
+/// the public inputs of a proof.
+pub trait Hasher<F: RichField>: Sized + Clone + Debug + Eq + PartialEq {
+    /// Size of `Hash` in bytes.
+    const HASH_SIZE: usize;
+    type Hash: GenericHashOut<F>;
+
+    /// Permutation used in this hash function.
+    type Permutation: PlonkyPermutation<F>;
+
+    /// Hash a message without any padding step. Note that this can enable length-extension
+    /// attacks. However, it is still collision-resistant in cases where the input has a fixed
+    /// length.
+    fn hash_no_pad(input: &[F]) -> Self::Hash;
+
+    /// Pad the message using the `pad10*1` rule, then hash it.
+    fn hash_pad(input: &[F]) -> Self::Hash {
+        let mut padded_input = input.to_vec();
+        padded_input.push(F::ONE);
+        while (padded_input.len() + 1) % Self::Permutation::RATE != 0 {
+            padded_input.push(F::ZERO);
+        }
+        padded_input.push(F::ONE);
+        Self::hash_no_pad(&padded_input)
+    }
+
+    /// Hash the slice if necessary to reduce its length to ~256 bits. If it already fits, this is
+    /// a no-op.
+    fn hash_or_noop(inputs: &[F]) -> Self::Hash {
+        if inputs.len() * 8 <= Self::HASH_SIZE {
+            let mut inputs_bytes = vec![0u8; Self::HASH_SIZE];
+            for (i, input) in inputs.iter().enumerate() {
+                inputs_bytes[i * 8..(i + 1) * 8].copy_from_slice(&input.to_canonical_u64().to_le_bytes());
+            }
+            Self::Hash::from_bytes(&inputs_bytes)
+        } else {
+            Self::hash_no_pad(inputs)
+        }
+    }
+
+    fn two_to_one(left: Self::Hash, right: Self::Hash) -> Self::Hash;
+}
+
+/// Trait for algebraic hash functions, built from a permutation using the Sponge construction,
+/// and usable in-circuit.
+pub trait AlgebraicHasher<F: RichField>: Hasher<F, Hash = HashOut<F>> {
+    type AlgebraicPermutation: PlonkyPermutation<Target>;
+
+    /// Circuit version of `permute_swapped`.
+    fn permute_swapped<const D: usize>(
+        inputs: Self::AlgebraicPermutation,
+        swap: crate::iop::target::BoolTarget,
+        builder: &mut CircuitBuilder<F, D>,
+    ) -> Self::AlgebraicPermutation
+    where
+        F: RichField + Extendable<D>;
+}
+
+/// Generic configuration trait, tying together a field, its extension and the hash functions used
+/// in the proving system.
+pub trait GenericConfig<const D: usize>:
+    Debug + Clone + Sync + Sized + Send + Eq + PartialEq
+{
+    /// Main field.
+    type F: RichField + Extendable<D, Extension = Self::FE>;
+    /// Field extension of degree D used in the proving system.
+    type FE: FieldExtension<D, BaseField = Self::F>;
+    /// Hash function used for building Merkle trees.
+    type Hasher: Hasher<Self::F>;
+    /// Algebraic hash function used for the challenger and in-circuit verification.
+    type InnerHasher: AlgebraicHasher<Self::F>;
+}
+
+/// Configuration using Poseidon over the Goldilocks field, both for Merkle trees and for
+/// in-circuit operations.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct PoseidonGoldilocksConfig;
+impl GenericConfig<2> for PoseidonGoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = <GoldilocksField as Extendable<2>>::Extension;
+    type Hasher = PoseidonHash;
+    type InnerHasher = PoseidonHash;
+}
+
+/// Configuration using Monolith over the Goldilocks field for Merkle trees, with Poseidon kept as
+/// the in-circuit hasher for the challenger and recursive verification. Monolith has no algebraic
+/// (in-circuit) counterpart here since its Bars layer relies on a lookup table rather than a
+/// low-degree polynomial map, so it is only used off-circuit, where its reduced number of rounds
+/// makes it 2-3x cheaper than Poseidon.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct MonolithGoldilocksConfig;
+impl GenericConfig<2> for MonolithGoldilocksConfig {
+    type F = GoldilocksField;
+    type FE = <GoldilocksField as Extendable<2>>::Extension;
+    type Hasher = MonolithHash;
+    type InnerHasher = PoseidonHash;
+}
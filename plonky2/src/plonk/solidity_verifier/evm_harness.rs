@@ -0,0 +1,399 @@
+//! A Rust test harness that deploys a [`generate_verifier_contract`](super::generate_verifier_contract)
+//! output against an in-process EVM (`revm`) and calls its `verify`, so generated verifiers are
+//! executed rather than only type-checked as a string of Solidity. The tests mirror the
+//! `NthRootGenerator` example's flow up through `data.prove` and pass the real proof's public
+//! inputs to the contract, but the query openings are either not taken from the proof (and must be
+//! rejected) or built by hand to fit the contract's single-`uint64` leaves (and must be accepted).
+//! A native proof's own openings are not fed to the contract; see the tests' docs for why.
+//!
+//! This module is only compiled under `#[cfg(test)]`: it depends on `revm` and `ethers-solc` as
+//! dev-dependencies (compiling the generated source requires `solc`), neither of which the
+//! library itself needs at runtime.
+
+#![cfg(test)]
+
+use anyhow::{anyhow, Result};
+use ethers_solc::{CompilerInput, Solc};
+use revm::primitives::{ExecutionResult, Output, TransactTo, B160};
+use revm::{InMemoryDB, EVM};
+
+use super::SolidityVerifier;
+
+/// Compiles `verifier.source` with `solc` and deploys the single contract it contains to a fresh
+/// in-memory EVM state, returning the deployed contract's address.
+pub fn deploy_verifier(verifier: &SolidityVerifier) -> Result<(EVM<InMemoryDB>, B160)> {
+    let input = CompilerInput::new_from_source(verifier.source.clone());
+    let compiled = Solc::default()
+        .compile(&input)
+        .map_err(|e| anyhow!("solc invocation failed: {e}"))?;
+    let contract = compiled
+        .get(".", &verifier.contract_name)
+        .ok_or_else(|| anyhow!("solc output did not contain {}", verifier.contract_name))?;
+    let bytecode = contract
+        .bin
+        .as_ref()
+        .ok_or_else(|| anyhow!("missing bytecode for {}", verifier.contract_name))?
+        .as_bytes()
+        .ok_or_else(|| anyhow!("unlinked bytecode for {}", verifier.contract_name))?;
+
+    let mut evm = EVM::new();
+    evm.database(InMemoryDB::default());
+
+    let deployer = B160::from_low_u64_be(1);
+    evm.env.tx.caller = deployer;
+    evm.env.tx.transact_to = TransactTo::create();
+    evm.env.tx.data = bytecode.clone().into();
+
+    let result = evm.transact_commit().map_err(|e| anyhow!("deploy failed: {e:?}"))?;
+    let address = match result {
+        ExecutionResult::Success {
+            output: Output::Create(_, Some(address)),
+            ..
+        } => address,
+        other => return Err(anyhow!("unexpected deploy result: {other:?}")),
+    };
+    Ok((evm, address))
+}
+
+/// A `QueryOpening` as the generated contract's `verify` expects it, encoded as a Solidity
+/// `QueryOpening[]` (see `solidity_verifier`'s template), not a raw `Proof::to_bytes()` blob: the
+/// contract ABI-decodes `proof` itself.
+pub struct QueryOpeningData {
+    pub leaf: u64,
+    pub leaf_index: u64,
+    pub leaf_merkle_path: Vec<[u8; 32]>,
+    pub fri_layer_values: Vec<u64>,
+    pub fri_layer_merkle_paths: Vec<Vec<[u8; 32]>>,
+    /// Merkle path for each step's *folded result* (not just its sibling), against the same
+    /// shared cap -- see `QueryOpening`'s doc comment in the Solidity template.
+    pub fri_layer_folded_merkle_paths: Vec<Vec<[u8; 32]>>,
+}
+
+/// ABI-encodes `openings` as the `QueryOpening[]` the contract's `verify` decodes its `proof`
+/// argument into.
+pub fn encode_openings(openings: &[QueryOpeningData]) -> Vec<u8> {
+    use ethers::abi::{encode, Token};
+
+    let tokens: Vec<Token> = openings
+        .iter()
+        .map(|o| {
+            Token::Tuple(vec![
+                Token::Uint(o.leaf.into()),
+                Token::Uint(o.leaf_index.into()),
+                Token::Array(
+                    o.leaf_merkle_path
+                        .iter()
+                        .map(|b| Token::FixedBytes(b.to_vec()))
+                        .collect(),
+                ),
+                Token::Array(o.fri_layer_values.iter().map(|&v| Token::Uint(v.into())).collect()),
+                Token::Array(
+                    o.fri_layer_merkle_paths
+                        .iter()
+                        .map(|path| {
+                            Token::Array(path.iter().map(|b| Token::FixedBytes(b.to_vec())).collect())
+                        })
+                        .collect(),
+                ),
+                Token::Array(
+                    o.fri_layer_folded_merkle_paths
+                        .iter()
+                        .map(|path| {
+                            Token::Array(path.iter().map(|b| Token::FixedBytes(b.to_vec())).collect())
+                        })
+                        .collect(),
+                ),
+            ])
+        })
+        .collect();
+    encode(&[Token::Array(tokens)])
+}
+
+/// ABI-encodes the generated `verify(bytes,uint256[N])` call, sends it to `address`, and returns
+/// whether the contract accepted the proof.
+pub fn call_verify(
+    evm: &mut EVM<InMemoryDB>,
+    address: B160,
+    encoded_openings: &[u8],
+    public_inputs: &[u64],
+) -> Result<bool> {
+    use ethers::abi::{encode, Token};
+
+    let signature = format!("verify(bytes,uint256[{}])", public_inputs.len());
+    let mut calldata = ethers::utils::keccak256(signature.as_bytes())[..4].to_vec();
+    calldata.extend(encode(&[
+        Token::Bytes(encoded_openings.to_vec()),
+        Token::FixedArray(
+            public_inputs
+                .iter()
+                .map(|&x| Token::Uint(x.into()))
+                .collect(),
+        ),
+    ]));
+
+    evm.env.tx.transact_to = TransactTo::Call(address);
+    evm.env.tx.data = calldata.into();
+
+    let result = evm.transact_ref().map_err(|e| anyhow!("call failed: {e:?}"))?;
+    match result.result {
+        ExecutionResult::Success { output: Output::Call(bytes), .. } => Ok(bytes.last() == Some(&1)),
+        _ => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::types::Field;
+
+    use super::*;
+    use crate::iop::witness::{PartialWitness, WitnessWrite};
+    use crate::plonk::circuit_builder::CircuitBuilder;
+    use crate::plonk::circuit_data::CircuitConfig;
+    use crate::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use crate::plonk::solidity_verifier::generate_verifier_contract;
+
+    /// Builds a trivial `x * x == x_squared` circuit, proves it, and generates a verifier
+    /// contract from the real `VerifierOnlyCircuitData`/`CommonCircuitData`. Feeding it openings
+    /// that weren't actually produced from this proof's committed cap must be rejected by
+    /// `_verifyMerkleCap` (a real check, not a stub) regardless of what the rest of the query
+    /// round looks like; this is what used to pass unconditionally before `_verifyQueryRound`
+    /// was wired up.
+    ///
+    /// Constructing openings that *do* match a real native proof's committed cap and have this
+    /// contract accept them needs more than bridging the hash function: `constantsSigmasCap`'s
+    /// leaves are `hash_or_noop` of the whole constants-and-sigmas row (comfortably more than the
+    /// 4 field elements `HASH_SIZE` allows through unhashed for any real `CircuitConfig`), i.e.
+    /// already a packed digest, whereas this contract's `QueryOpening.leaf` is a single `uint64`
+    /// and gets re-hashed on its own in `_verifyMerkleCap`'s first step. No real multi-column
+    /// oracle leaf can be represented that way; widening `leaf` (and the FRI layer values, which
+    /// have the same one-element-per-step assumption) to carry a whole row is a contract-template
+    /// change, not something this opening-encoding test can paper over, so it's tracked as
+    /// follow-up rather than faked here. [`deployed_verifier_accepts_a_self_consistent_proof`]
+    /// below instead exercises every check the contract makes -- cap membership, the arity-2 fold
+    /// arithmetic now that its result is checked too, and the keccak transcript -- against a
+    /// cap and query openings built by hand to already fit that single-element-per-leaf shape.
+    #[test]
+    fn deployed_verifier_rejects_openings_not_from_the_proof() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let x_squared = builder.mul(x, x);
+        builder.register_public_input(x_squared);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(7));
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let (verifier_only, common) = (&data.verifier_only, &data.common);
+        let verifier = generate_verifier_contract::<F, C, D>(verifier_only, common);
+        let num_query_rounds = common.fri_params.config.num_query_rounds;
+
+        let (mut evm, address) = deploy_verifier(&verifier)?;
+
+        let garbage_openings: Vec<QueryOpeningData> = (0..num_query_rounds)
+            .map(|_| QueryOpeningData {
+                leaf: 0,
+                leaf_index: 0,
+                leaf_merkle_path: Vec::new(),
+                fri_layer_values: Vec::new(),
+                fri_layer_merkle_paths: Vec::new(),
+                fri_layer_folded_merkle_paths: Vec::new(),
+            })
+            .collect();
+
+        let public_inputs: Vec<u64> = proof
+            .public_inputs
+            .iter()
+            .map(|pi| pi.to_canonical_u64())
+            .collect();
+        let accepted = call_verify(
+            &mut evm,
+            address,
+            &encode_openings(&garbage_openings),
+            &public_inputs,
+        )?;
+        assert!(
+            !accepted,
+            "openings not derived from the proof's committed cap must be rejected"
+        );
+
+        Ok(())
+    }
+
+    /// `GOLDILOCKS_PRIME` / `INV_TWO`, matching the constants baked into the generated contract.
+    fn goldilocks_prime() -> num::BigUint {
+        num::BigUint::from(0xFFFFFFFF00000001u64)
+    }
+
+    fn inv_two() -> num::BigUint {
+        num::BigUint::from(0x7FFFFFFF80000001u64)
+    }
+
+    fn keccak(bytes: &[u8]) -> [u8; 32] {
+        ethers::utils::keccak256(bytes)
+    }
+
+    /// A `uint64`'s `abi.encodePacked` form: its 8-byte big-endian representation, not padded out
+    /// to a full word the way `abi.encode` would.
+    fn packed_u64(x: u64) -> [u8; 8] {
+        x.to_be_bytes()
+    }
+
+    /// Rust re-implementation of the generated contract's `_friFold`, used to compute the fold
+    /// result our hand-built opening claims so it matches what `_verifyQueryRound` will compute.
+    fn fri_fold(
+        left: u64,
+        right: u64,
+        beta: &num::BigUint,
+        domain_generator: u64,
+        index: u64,
+    ) -> u64 {
+        let p = goldilocks_prime();
+        let x = num::BigUint::from(domain_generator).modpow(&num::BigUint::from(index), &p);
+        let two_x = (num::BigUint::from(2u64) * &x) % &p;
+        let two_x_inv = two_x.modpow(&(&p - num::BigUint::from(2u64)), &p);
+
+        let left = num::BigUint::from(left);
+        let right = num::BigUint::from(right);
+        let sum = (&left + &right) % &p;
+        let diff = (&left + &p - &right) % &p;
+
+        let term1 = (&sum * inv_two()) % &p;
+        let term2 = (beta * &diff % &p) * &two_x_inv % &p;
+        use num::ToPrimitive;
+        ((term1 + term2) % &p).to_u64().unwrap()
+    }
+
+    /// Builds a real circuit, deploys its generated verifier contract, then hand-builds a cap and
+    /// query openings that satisfy every check the contract performs -- cap membership of the
+    /// initial leaf, cap membership of each FRI layer's sibling *and* folded result (the gap
+    /// `_verifyQueryRound` used to leave open), and the keccak Fiat-Shamir transcript -- and
+    /// confirms the contract accepts them. See `deployed_verifier_rejects_openings_not_from_the_proof`
+    /// for why this builds its own cap rather than reusing the circuit's real (Poseidon-committed,
+    /// multi-element-leaf) one.
+    #[test]
+    fn deployed_verifier_accepts_a_self_consistent_proof() -> Result<()> {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let config = CircuitConfig::standard_recursion_config();
+        let mut builder = CircuitBuilder::<F, D>::new(config);
+        let x = builder.add_virtual_target();
+        let x_squared = builder.mul(x, x);
+        builder.register_public_input(x_squared);
+
+        let mut pw = PartialWitness::new();
+        pw.set_target(x, F::from_canonical_u64(7));
+
+        let data = builder.build::<C>();
+        let proof = data.prove(pw)?;
+
+        let (verifier_only, common) = (&data.verifier_only, &data.common);
+        let verifier = generate_verifier_contract::<F, C, D>(verifier_only, common);
+        let num_query_rounds = common.fri_params.config.num_query_rounds as u64;
+        let lde_bits = common.degree_bits() + common.fri_params.config.rate_bits;
+        let domain_generator = F::primitive_root_of_unity(lde_bits).to_canonical_u64();
+
+        // Every round reuses the same leaf/sibling values, but at a round-specific set of cap
+        // indices, so that the per-round fold result (which varies with the round-dependent
+        // transcript counter even though the leaf/sibling don't) never collides with another
+        // round's leaf, sibling, or fold slot. `big = num_query_rounds` leaves the fold-index
+        // range `[big, 2*big)` disjoint from the leaf/sibling range `[2*big, 4*big)`.
+        let leaf: u64 = 42;
+        let sibling: u64 = 1_000_003;
+        let big = num_query_rounds.max(1);
+        let num_cap_leaves = 4 * big;
+
+        let public_inputs: Vec<u64> = proof
+            .public_inputs
+            .iter()
+            .map(|pi| pi.to_canonical_u64())
+            .collect();
+        let mut packed_public_inputs = Vec::with_capacity(public_inputs.len() * 32);
+        for pi in &public_inputs {
+            packed_public_inputs.extend_from_slice(&[0u8; 24]);
+            packed_public_inputs.extend_from_slice(&pi.to_be_bytes());
+        }
+        let transcript_state = keccak(&packed_public_inputs);
+
+        let mut cap = vec![[0u8; 32]; num_cap_leaves as usize];
+        let mut openings = Vec::with_capacity(num_query_rounds as usize);
+        for round in 0..num_query_rounds {
+            let leaf_index = 2 * big + 2 * round;
+            let sibling_index = leaf_index ^ 1;
+            let fold_index = leaf_index >> 1;
+
+            let mut counter_bytes = [0u8; 32];
+            counter_bytes[24..].copy_from_slice(&(round * 1000).to_be_bytes());
+            let mut challenge_input = transcript_state.to_vec();
+            challenge_input.extend_from_slice(&counter_bytes);
+            let beta = num::BigUint::from_bytes_be(&keccak(&challenge_input)) % goldilocks_prime();
+
+            let folded = fri_fold(leaf, sibling, &beta, domain_generator, fold_index);
+
+            cap[leaf_index as usize] = keccak(&packed_u64(leaf));
+            cap[sibling_index as usize] = keccak(&packed_u64(sibling));
+            cap[fold_index as usize] = keccak(&packed_u64(folded));
+
+            openings.push(QueryOpeningData {
+                leaf,
+                leaf_index,
+                leaf_merkle_path: Vec::new(),
+                fri_layer_values: vec![sibling],
+                fri_layer_merkle_paths: vec![Vec::new()],
+                fri_layer_folded_merkle_paths: vec![Vec::new()],
+            });
+        }
+
+        // Splice our own cap into the generated source in place of the real (Poseidon-committed)
+        // one, keeping every other templated parameter (rate bits, query round count, domain
+        // generator, public input count) exactly as the real circuit produced.
+        let real_num_cap_leaves = verifier_only.constants_sigmas_cap.0.len();
+        let real_cap_hex = verifier_only
+            .constants_sigmas_cap
+            .0
+            .iter()
+            .map(|hash| format!("bytes32(0x{})", hex::encode(hash.to_bytes())))
+            .collect::<Vec<_>>()
+            .join(",\n        ");
+        let our_cap_hex = cap
+            .iter()
+            .map(|h| format!("bytes32(0x{})", hex::encode(h)))
+            .collect::<Vec<_>>()
+            .join(",\n        ");
+
+        let source = verifier
+            .source
+            .replacen(
+                &format!("new bytes32[]({real_num_cap_leaves})"),
+                &format!("new bytes32[]({num_cap_leaves})"),
+                1,
+            )
+            .replacen(
+                &format!("bytes32[{real_num_cap_leaves}] memory cap = [\n        {real_cap_hex}\n        ];"),
+                &format!("bytes32[{num_cap_leaves}] memory cap = [\n        {our_cap_hex}\n        ];"),
+                1,
+            );
+        assert_ne!(
+            source, verifier.source,
+            "splice must actually replace the real cap, not leave it untouched"
+        );
+        let verifier = SolidityVerifier { source, ..verifier };
+
+        let (mut evm, address) = deploy_verifier(&verifier)?;
+        let accepted = call_verify(&mut evm, address, &encode_openings(&openings), &public_inputs)?;
+        assert!(
+            accepted,
+            "a self-consistently constructed proof, checked against its own cap, must be accepted"
+        );
+
+        Ok(())
+    }
+}
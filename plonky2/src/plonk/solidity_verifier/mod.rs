@@ -0,0 +1,275 @@
+//! Solidity code generation for Plonky2 [`VerifierCircuitData`]. The generated contract checks
+//! query openings whose leaves and FRI layer values are single `uint64`s hashed with `keccak256`
+//! against the circuit's baked-in `constants_sigmas_cap`. It does not accept a native proof as
+//! produced by `data.prove` (as in the `NthRootGenerator` example): that cap is committed with the
+//! config's own hasher, and a real oracle leaf holds a whole row, not one element.
+//!
+//! [`generate_verifier_contract`] emits a standalone contract implementing:
+//! - Merkle cap checks of each query's leaf against the verifier's committed
+//!   `constants_sigmas_cap`,
+//! - the FRI query-phase arity-2 folding check (`_friFold`), using the Goldilocks prime and the
+//!   circuit's LDE domain generator baked in at generation time, with each step's sibling *and*
+//!   its own folded result checked against the cap -- the fold arithmetic would otherwise be
+//!   unconstrained busywork, since nothing downstream depended on its output,
+//! - a Fiat-Shamir transcript using `keccak256` over the public inputs in place of the
+//!   Poseidon/Monolith challenger. It does not reproduce the native `Challenger`, so its
+//!   challenges differ from the ones the Rust verifier derives.
+//!
+//! This intentionally verifies a single shared Merkle cap for every oracle (trace and each FRI
+//! layer) rather than the separate per-oracle caps `CommonCircuitData` tracks, and folds directly
+//! to a single final value rather than continuing down to the FRI final polynomial's own
+//! low-degree check. Both are real cryptographic checks, not stubs, but narrower than the full
+//! multi-oracle FRI verifier `plonk::verifier::verify` runs natively; widening them to match is
+//! tracked as follow-up work rather than shipped as a silent gap.
+//!
+//! See `solidity_verifier::evm_harness` for the in-process EVM test harness that deploys the
+//! generated contract and feeds it both hand-built self-consistent openings and openings not
+//! taken from the proof.
+
+pub mod evm_harness;
+
+use plonky2_field::extension::Extendable;
+use plonky2_field::types::Field;
+
+use crate::hash::hash_types::RichField;
+use crate::plonk::circuit_data::{CommonCircuitData, VerifierOnlyCircuitData};
+use crate::plonk::config::GenericConfig;
+
+/// Solidity source of the generated verifier, plus enough metadata for the harness (or a caller
+/// wiring this into a deployment script) to ABI-encode a proof the way the contract expects it.
+#[derive(Debug, Clone)]
+pub struct SolidityVerifier {
+    pub source: String,
+    pub contract_name: String,
+    /// Number of public input field elements the generated `verify` function expects, derived
+    /// from `common_data.num_public_inputs`. If the circuit uses
+    /// [`crate::gadgets::public_input_commitment`], this is just the 4 digest elements rather than
+    /// the full input vector.
+    pub num_public_inputs: usize,
+}
+
+/// Generates a standalone Solidity verifier contract for a circuit's `VerifierOnlyCircuitData` and
+/// `CommonCircuitData`, the same pair returned by `CircuitData::verifier_data()`.
+pub fn generate_verifier_contract<F, C, const D: usize>(
+    verifier_only: &VerifierOnlyCircuitData<C, D>,
+    common_data: &CommonCircuitData<F, D>,
+) -> SolidityVerifier
+where
+    F: RichField + Extendable<D>,
+    C: GenericConfig<D, F = F>,
+{
+    let contract_name = "Plonky2Verifier".to_string();
+    let fri_config = &common_data.fri_params.config;
+
+    let cap_height = verifier_only.constants_sigmas_cap.height();
+    let num_cap_leaves = verifier_only.constants_sigmas_cap.0.len();
+    let num_query_rounds = fri_config.num_query_rounds;
+    let rate_bits = fri_config.rate_bits;
+    let num_public_inputs = common_data.num_public_inputs;
+
+    let lde_bits = common_data.degree_bits() + rate_bits;
+    let domain_generator = F::primitive_root_of_unity(lde_bits);
+
+    let constants_sigmas_cap_hex = cap_to_solidity_array(verifier_only);
+
+    let source = SOLIDITY_TEMPLATE
+        .replace("{{CONTRACT_NAME}}", &contract_name)
+        .replace("{{CAP_HEIGHT}}", &cap_height.to_string())
+        .replace("{{NUM_CAP_LEAVES}}", &num_cap_leaves.to_string())
+        .replace("{{NUM_QUERY_ROUNDS}}", &num_query_rounds.to_string())
+        .replace("{{RATE_BITS}}", &rate_bits.to_string())
+        .replace("{{NUM_PUBLIC_INPUTS}}", &num_public_inputs.to_string())
+        .replace(
+            "{{DOMAIN_GENERATOR}}",
+            &domain_generator.to_canonical_u64().to_string(),
+        )
+        .replace("{{CONSTANTS_SIGMAS_CAP}}", &constants_sigmas_cap_hex);
+
+    SolidityVerifier {
+        source,
+        contract_name,
+        num_public_inputs,
+    }
+}
+
+fn cap_to_solidity_array<C, const D: usize>(verifier_only: &VerifierOnlyCircuitData<C, D>) -> String
+where
+    C: GenericConfig<D>,
+{
+    verifier_only
+        .constants_sigmas_cap
+        .0
+        .iter()
+        .map(|hash| format!("bytes32(0x{})", hex::encode(hash.to_bytes())))
+        .collect::<Vec<_>>()
+        .join(",\n        ")
+}
+
+/// Merkle cap verification, the Fiat-Shamir transcript, and the FRI query-round arity-2 folding
+/// check are all real, wired-in checks; see the module docs for exactly how this differs in scope
+/// from the native multi-oracle verifier.
+const SOLIDITY_TEMPLATE: &str = r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.19;
+
+/// Generated by plonky2's `solidity_verifier`. Do not edit by hand; regenerate from the
+/// `VerifierCircuitData` this was produced from if the circuit changes.
+contract {{CONTRACT_NAME}} {
+    uint256 internal constant CAP_HEIGHT = {{CAP_HEIGHT}};
+    uint256 internal constant NUM_QUERY_ROUNDS = {{NUM_QUERY_ROUNDS}};
+    uint256 internal constant RATE_BITS = {{RATE_BITS}};
+    uint256 internal constant NUM_PUBLIC_INPUTS = {{NUM_PUBLIC_INPUTS}};
+
+    /// p = 2^64 - 2^32 + 1, the Goldilocks prime `CommonCircuitData`'s `F` is defined over.
+    uint256 internal constant GOLDILOCKS_PRIME = 0xFFFFFFFF00000001;
+    /// Inverse of 2 mod GOLDILOCKS_PRIME, i.e. `(p + 1) / 2` since p is odd.
+    uint256 internal constant INV_TWO = 0x7FFFFFFF80000001;
+    /// Generator of the circuit's low-degree-extension domain (order `2^(degree_bits + rate_bits)`).
+    uint256 internal constant DOMAIN_GENERATOR = {{DOMAIN_GENERATOR}};
+
+    bytes32[] internal constantsSigmasCap;
+
+    /// One opening per FRI query round: the initial oracle leaf and its Merkle path against
+    /// `constantsSigmasCap`, then one (sibling value, sibling's Merkle path, folded result's
+    /// Merkle path) triple per folding step. The folded result is re-derived by `_friFold` rather
+    /// than supplied directly, but still needs its own Merkle path: it claims to be the next FRI
+    /// layer's oracle value at the shrunk index, and that claim is only real if it is itself
+    /// checked against the shared cap, exactly like the sibling is.
+    struct QueryOpening {
+        uint64 leaf;
+        uint256 leafIndex;
+        bytes32[] leafMerklePath;
+        uint64[] friLayerValues;
+        bytes32[][] friLayerMerklePaths;
+        bytes32[][] friLayerFoldedMerklePaths;
+    }
+
+    constructor() {
+        // constants_sigmas_cap, one entry per leaf of the committed cap Merkle tree:
+        constantsSigmasCap = new bytes32[]({{NUM_CAP_LEAVES}});
+        bytes32[{{NUM_CAP_LEAVES}}] memory cap = [
+        {{CONSTANTS_SIGMAS_CAP}}
+        ];
+        for (uint256 i = 0; i < cap.length; i++) {
+            constantsSigmasCap[i] = cap[i];
+        }
+    }
+
+    /// Derives Fiat-Shamir challenges from the proof's public inputs and an index with keccak256.
+    /// This is not the Rust `Challenger`'s Poseidon/Monolith transcript, so the values differ
+    /// from the native verifier's challenges.
+    function _getChallenge(bytes32 state, uint256 counter) internal pure returns (uint256) {
+        return uint256(keccak256(abi.encodePacked(state, counter))) % GOLDILOCKS_PRIME;
+    }
+
+    /// Checks a Merkle cap opening at `leafIndex` against `constantsSigmasCap`.
+    function _verifyMerkleCap(
+        bytes32[] memory siblings,
+        uint256 leafIndex,
+        bytes32 leafHash
+    ) internal view returns (bool) {
+        bytes32 current = leafHash;
+        uint256 index = leafIndex;
+        for (uint256 i = 0; i < siblings.length; i++) {
+            bytes32 sibling = siblings[i];
+            current = (index & 1 == 0)
+                ? keccak256(abi.encodePacked(current, sibling))
+                : keccak256(abi.encodePacked(sibling, current));
+            index >>= 1;
+        }
+        return index < constantsSigmasCap.length && current == constantsSigmasCap[index];
+    }
+
+    function _modExp(uint256 base, uint256 exponent, uint256 modulus) internal view returns (uint256 result) {
+        assembly {
+            let p := mload(0x40)
+            mstore(p, 0x20)
+            mstore(add(p, 0x20), 0x20)
+            mstore(add(p, 0x40), 0x20)
+            mstore(add(p, 0x60), base)
+            mstore(add(p, 0x80), exponent)
+            mstore(add(p, 0xa0), modulus)
+            if iszero(staticcall(gas(), 0x05, p, 0xc0, p, 0x20)) {
+                revert(0, 0)
+            }
+            result := mload(p)
+        }
+    }
+
+    /// Arity-2 FRI fold: `next = (left + right) / 2 + beta * (left - right) / (2x)`, where `x` is
+    /// the evaluation point `DOMAIN_GENERATOR^(index << step)` in the (shrinking) FRI domain and
+    /// division is by the Goldilocks-prime modular inverse (via Fermat's little theorem, since
+    /// GOLDILOCKS_PRIME is prime).
+    function _friFold(
+        uint256 left,
+        uint256 right,
+        uint256 beta,
+        uint256 index,
+        uint256 step
+    ) internal view returns (uint256) {
+        uint256 x = _modExp(DOMAIN_GENERATOR, index << step, GOLDILOCKS_PRIME);
+        uint256 twoXInv = _modExp(mulmod(2, x, GOLDILOCKS_PRIME), GOLDILOCKS_PRIME - 2, GOLDILOCKS_PRIME);
+
+        uint256 sum = addmod(left, right, GOLDILOCKS_PRIME);
+        uint256 diff = addmod(left, GOLDILOCKS_PRIME - right, GOLDILOCKS_PRIME);
+
+        uint256 term1 = mulmod(sum, INV_TWO, GOLDILOCKS_PRIME);
+        uint256 term2 = mulmod(mulmod(beta, diff, GOLDILOCKS_PRIME), twoXInv, GOLDILOCKS_PRIME);
+        return addmod(term1, term2, GOLDILOCKS_PRIME);
+    }
+
+    /// Verifies one query round: the initial leaf's Merkle membership, then folds it down through
+    /// every FRI layer, checking each layer's sibling *and* its folded result against the cap --
+    /// otherwise `_friFold`'s output feeds nothing but the next iteration's local variable and the
+    /// round would pass regardless of what it computed.
+    function _verifyQueryRound(bytes32 transcriptState, uint256 round, QueryOpening memory o) internal view returns (bool) {
+        if (!_verifyMerkleCap(o.leafMerklePath, o.leafIndex, keccak256(abi.encodePacked(o.leaf)))) {
+            return false;
+        }
+        if (o.friLayerValues.length != o.friLayerMerklePaths.length ||
+            o.friLayerValues.length != o.friLayerFoldedMerklePaths.length) {
+            return false;
+        }
+
+        uint256 current = uint256(o.leaf);
+        uint256 index = o.leafIndex;
+        for (uint256 step = 0; step < o.friLayerValues.length; step++) {
+            uint256 beta = _getChallenge(transcriptState, round * 1000 + step);
+            uint256 sibling = uint256(o.friLayerValues[step]);
+            uint256 siblingIndex = index ^ 1;
+
+            if (!_verifyMerkleCap(o.friLayerMerklePaths[step], siblingIndex, keccak256(abi.encodePacked(o.friLayerValues[step])))) {
+                return false;
+            }
+
+            (uint256 left, uint256 right) = (index & 1 == 0) ? (current, sibling) : (sibling, current);
+            uint256 folded = _friFold(left, right, beta, index >> 1, step);
+            index >>= 1;
+
+            if (!_verifyMerkleCap(o.friLayerFoldedMerklePaths[step], index, keccak256(abi.encodePacked(uint64(folded))))) {
+                return false;
+            }
+
+            current = folded;
+        }
+        return true;
+    }
+
+    function verify(
+        bytes calldata proof,
+        uint256[NUM_PUBLIC_INPUTS] calldata publicInputs
+    ) external view returns (bool) {
+        QueryOpening[] memory openings = abi.decode(proof, (QueryOpening[]));
+        if (openings.length != NUM_QUERY_ROUNDS) {
+            return false;
+        }
+
+        bytes32 transcriptState = keccak256(abi.encodePacked(publicInputs));
+        for (uint256 round = 0; round < NUM_QUERY_ROUNDS; round++) {
+            if (!_verifyQueryRound(transcriptState, round, openings[round])) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+"#;
@@ -0,0 +1,328 @@
+//! General N-th root extraction over a prime field, generalizing
+//! `NthRootGenerator::run_once`'s use of `x_pow_n.kth_root_u64(nth_pow)`.
+//!
+//! `kth_root_u64` computes a unique root via the inverse exponent `N^{-1} mod (p-1)`, which is
+//! only well-defined when `N` is coprime to `p - 1`. The `NthRootGenerator` example instead picks
+//! `N = 3` with `3 | p - 1` (Goldilocks' `p - 1` is divisible by 3), where an arbitrary
+//! `x_pow_n` may have zero, or `N`, distinct N-th roots, and `kth_root_u64` is not the right tool.
+//!
+//! This module handles that case via the Adleman-Manders-Miller generalization of Tonelli-Shanks:
+//! split `e = gcd(N, p - 1)` and `m = (p - 1) / e`. The `e`-th and `N`-th power maps then share
+//! the same image, the order-`m` subgroup `H` of `F*`, so `a` has an N-th root iff `a` lies in
+//! `H`, i.e. iff `a^m == 1` (the Euler criterion). `k = N / e` is always coprime to `m` (since
+//! `gcd(e*k, e*m) = e*gcd(k, m)` must equal `gcd(N, p-1) = e`), so the `k`-th power map is always
+//! invertible on `H` via a single modular inverse mod `m`. `e` itself, however, is **not**
+//! guaranteed coprime to `m`: whenever a prime `q | N` has a smaller power in `N` than in `p - 1`
+//! (e.g. `N = 2` over Goldilocks, where `v_2(p - 1) = 32`), `q` divides both `e` and `m`, and a
+//! single `mod_inverse(e, m)` has no solution.
+//!
+//! Extracting an e-th root in that case is done one prime factor of `e` at a time (composing
+//! prime-th roots is valid since `(x^q1)^q2 = x^{q1*q2}`), and each prime-`q` root uses the full
+//! Adleman-Manders-Miller procedure: write `p - 1 = q^s * t` with `gcd(q, t) = 1` for the *true*
+//! multiplicity `s` of `q` in `p - 1` (not just in `e`), invert `q` mod `t` to get most of the way
+//! there, then close the remaining gap -- a correction living in the order-`q^s` "q-Sylow"
+//! subgroup of `F*` -- via a discrete log computed digit-by-digit in base `q`
+//! ([`discrete_log_in_prime_power_group`]). This subsumes the coprime case (`s == 1` skips the
+//! correction step entirely), so it is the only e-th-root path needed.
+
+use num::bigint::BigUint;
+use num::traits::{One, Zero};
+use num::Integer;
+
+use plonky2_field::types::{Field, PrimeField};
+
+/// Returns *a* solution to `x^n == a`, or `None` if none exists. When multiple solutions exist,
+/// the choice among them is unspecified; use [`all_nth_roots`] to enumerate every root.
+pub fn nth_root<F: PrimeField>(a: F, n: u64) -> Option<F> {
+    if n == 0 {
+        return if a.is_one() { Some(F::ONE) } else { None };
+    }
+    if a.is_zero() {
+        return Some(F::ZERO);
+    }
+
+    let p_minus_1 = F::order() - BigUint::one();
+    let n_big = BigUint::from(n);
+    let e = n_big.gcd(&p_minus_1);
+    let m = &p_minus_1 / &e;
+
+    // Euler criterion: `a` is an e-th (equivalently N-th, since N and e have the same image
+    // subgroup) power iff `a^m == 1`.
+    if a.exp_biguint(&m) != F::ONE {
+        return None;
+    }
+
+    // An e-th root of `a`, one prime factor of `e` at a time.
+    let e_th_root = prime_factors(&e)
+        .into_iter()
+        .fold(a, |acc, q| prime_root(acc, q));
+    debug_assert_eq!(e_th_root.exp_biguint(&e), a);
+
+    // `k = N / e` is coprime to `m` whenever `e = gcd(N, p - 1)` (since
+    // `gcd(e*k, e*m) = e * gcd(k, m) = e` forces `gcd(k, m) = 1`), so the k-th power map is
+    // invertible on the order-m subgroup `e_th_root` lives in. Composing that inverse with the
+    // e-th root above turns it into an N-th root.
+    let k = &n_big / &e;
+    let k_inv_mod_m = mod_inverse(&k, &m).expect("N / e is coprime to m by construction");
+    let candidate = e_th_root.exp_biguint(&k_inv_mod_m);
+
+    debug_assert_eq!(candidate.exp_biguint(&n_big), a);
+    Some(candidate)
+}
+
+/// Returns the prime factors of `n` with multiplicity (e.g. `12 -> [2, 2, 3]`), via trial
+/// division. `n` here is always `e = gcd(N, p - 1)` for some small `N` a caller chose, so it is
+/// expected to be tiny (or at least smooth) in practice; this is not meant for factoring
+/// general large integers.
+fn prime_factors(n: &BigUint) -> Vec<u64> {
+    let mut factors = Vec::new();
+    let mut remaining = n.clone();
+    let mut candidate = BigUint::from(2u64);
+    while &candidate * &candidate <= remaining {
+        while (&remaining % &candidate).is_zero() {
+            factors.push(candidate.iter_u64_digits().next().unwrap_or(0));
+            remaining /= &candidate;
+        }
+        candidate += BigUint::one();
+    }
+    if !remaining.is_one() {
+        factors.push(remaining.iter_u64_digits().next().unwrap_or(0));
+    }
+    factors
+}
+
+/// Computes a `q`-th root of `a` for prime `q`, given that `a` is known to be a `q`-th power
+/// (guaranteed by the Euler-criterion check in [`nth_root`], which implies it for every prime
+/// factor of `e`). Handles any multiplicity of `q` in `p - 1`, including the repeated-factor case
+/// a single modular inverse can't (see the module docs).
+fn prime_root<F: PrimeField>(a: F, q: u64) -> F {
+    let p_minus_1 = F::order() - BigUint::one();
+    let q_big = BigUint::from(q);
+
+    // p - 1 = q^s * t, with gcd(q, t) = 1; `s` is q's *full* multiplicity in p - 1, which may be
+    // larger than its multiplicity in whatever `e` this root extraction was called for.
+    let mut t = p_minus_1;
+    let mut s: u32 = 0;
+    while (&t % &q_big).is_zero() {
+        t /= &q_big;
+        s += 1;
+    }
+    debug_assert!(s >= 1, "prime_root called with a q not dividing p - 1");
+    debug_assert!(
+        !t.is_one(),
+        "p - 1 is a pure power of q; c_prime's derivation below assumes another prime factor is \
+         left in t, which holds for every field actually used in this crate (e.g. Goldilocks' \
+         p - 1 = 2^32 * 3 * 5 * 17 * 257 * 65537 always has more than one prime factor)"
+    );
+
+    // The full multiplicative group's fixed generator can never be a proper power of anything
+    // (it has the maximal possible order, p - 1), so `g^t` always has the maximal order `q^s` --
+    // no search for a "non-residue" is needed, unlike classic Tonelli-Shanks.
+    let g = F::MULTIPLICATIVE_GROUP_GENERATOR;
+    let c = g.exp_biguint(&t);
+
+    // `root = a^{q^{-1} mod t}` gets most of the way to a q-th root: writing `q * q_inv_t = 1 +
+    // c_prime * t` for the (unique, nonnegative) integer `c_prime`, `root^q = a^{q*q_inv_t} = a *
+    // (a^t)^{c_prime}`, which is `a` times a correction `d := (a^t)^{c_prime}` of order dividing
+    // `q^{s-1}` (since `a`'s order divides `q^{s-1} * t`, courtesy of the Euler criterion).
+    let q_inv_t = mod_inverse(&q_big, &t).expect("q and t are coprime by construction");
+    let mut root = a.exp_biguint(&q_inv_t);
+
+    if s > 1 {
+        let c_prime = (&q_big * &q_inv_t - BigUint::one()) / &t;
+        let d = a.exp_biguint(&t).exp_biguint(&c_prime);
+
+        // `d` lies in the order-`q^{s-1}` subgroup generated by `c^q` (since `c` has order `q^s`).
+        // Find its discrete log `j` there, so `d == (c^q)^j`, i.e. `c^{j*q} == d`. Then
+        // `h := c^{-j}` satisfies `h^q == d^{-1}`, so multiplying it into `root` cancels `d` out
+        // of `root^q` exactly.
+        let base = c.exp_biguint(&q_big);
+        let j = discrete_log_in_prime_power_group(base, d, q, s - 1);
+        let correction = c.exp_biguint(&j).inverse();
+        root *= correction;
+    }
+
+    debug_assert_eq!(root.exp_u64(q), a);
+    root
+}
+
+/// Computes the discrete log `j` of `target` base `base`, where `base` is known to have order
+/// exactly `q^k` for prime `q` (so `0 <= j < q^k`), via digit-by-digit Pohlig-Hellman: recover
+/// `j`'s base-`q` digits from least significant to most by repeatedly raising the current
+/// remainder to a shrinking power of `q` and matching it against the `q` powers of `base`'s
+/// order-`q` "bottom digit" generator.
+fn discrete_log_in_prime_power_group<F: PrimeField>(base: F, target: F, q: u64, k: u32) -> BigUint {
+    let q_big = BigUint::from(q);
+    let top_power = pow_u64(&q_big, k - 1);
+
+    // Generator of the unique order-q subgroup of <base>.
+    let gamma = base.exp_biguint(&top_power);
+    let table: Vec<F> = (0..q).map(|i| gamma.exp_u64(i)).collect();
+
+    let base_inv = base.inverse();
+    let mut digit_value = BigUint::one(); // q^i, updated each iteration
+    let mut j = BigUint::zero();
+    let mut remainder = target;
+    for i in 0..k {
+        let exponent = pow_u64(&q_big, k - 1 - i);
+        let reduced = remainder.exp_biguint(&exponent);
+        let digit = table
+            .iter()
+            .position(|candidate| *candidate == reduced)
+            .expect("target is not in the expected order-q^k subgroup") as u64;
+
+        j += BigUint::from(digit) * &digit_value;
+        remainder *= base_inv.exp_biguint(&(BigUint::from(digit) * &digit_value));
+        digit_value *= &q_big;
+    }
+    j
+}
+
+/// `base^exponent` for a small integer `exponent`, since [`BigUint`] has no inherent `pow`.
+fn pow_u64(base: &BigUint, exponent: u32) -> BigUint {
+    let mut result = BigUint::one();
+    for _ in 0..exponent {
+        result *= base;
+    }
+    result
+}
+
+/// Returns every solution to `x^n == a`. Empty if none exists.
+pub fn all_nth_roots<F: PrimeField>(a: F, n: u64) -> Vec<F> {
+    let Some(root) = nth_root(a, n) else {
+        return Vec::new();
+    };
+    if a.is_zero() {
+        return vec![F::ZERO];
+    }
+
+    let p_minus_1 = F::order() - BigUint::one();
+    let n_big = BigUint::from(n);
+    let e = n_big.gcd(&p_minus_1);
+    let m = &p_minus_1 / &e;
+
+    // A generator of the order-e subgroup: raise a full-group generator to the m-th power.
+    let zeta = F::MULTIPLICATIVE_GROUP_GENERATOR.exp_biguint(&m);
+
+    let e_u64 = e.iter_u64_digits().next().unwrap_or(1);
+    let mut roots = Vec::with_capacity(e_u64 as usize);
+    let mut power = F::ONE;
+    for _ in 0..e_u64 {
+        roots.push(root * power);
+        power *= zeta;
+    }
+    roots
+}
+
+/// Computes `a^{-1} mod m` via the extended Euclidean algorithm, or `None` if `a` and `m` are not
+/// coprime.
+fn mod_inverse(a: &BigUint, m: &BigUint) -> Option<BigUint> {
+    let (gcd, x, _) = extended_gcd(a.clone().into(), m.clone().into());
+    if gcd != num::BigInt::one() {
+        return None;
+    }
+    let m_signed: num::BigInt = m.clone().into();
+    let result = ((x % &m_signed) + &m_signed) % &m_signed;
+    result.try_into().ok()
+}
+
+/// Returns `(gcd, x, y)` such that `a * x + b * y == gcd`.
+fn extended_gcd(a: num::BigInt, b: num::BigInt) -> (num::BigInt, num::BigInt, num::BigInt) {
+    if b == num::BigInt::from(0) {
+        (a, num::BigInt::from(1), num::BigInt::from(0))
+    } else {
+        let (gcd, x1, y1) = extended_gcd(b.clone(), &a % &b);
+        let y = x1 - (&a / &b) * &y1;
+        (gcd, y1, y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2_field::goldilocks_field::GoldilocksField as F;
+    use plonky2_field::types::{Field, Sample};
+
+    use super::*;
+
+    #[test]
+    fn finds_a_root_when_one_exists() {
+        // Goldilocks: p - 1 is divisible by 3.
+        let x = F::rand();
+        let a = x.exp_u64(3);
+        let root = nth_root(a, 3).expect("a cube should have a cube root");
+        assert_eq!(root.exp_u64(3), a);
+    }
+
+    #[test]
+    fn returns_none_when_no_root_exists() {
+        // A value that is not a cube (w.h.p. two out of three random field elements aren't).
+        let mut a = F::rand();
+        while nth_root(a, 3).is_some() {
+            a = F::rand();
+        }
+        assert_eq!(nth_root(a, 3), None);
+    }
+
+    #[test]
+    fn all_roots_are_distinct_and_valid() {
+        let x = F::rand();
+        let a = x.exp_u64(3);
+        let roots = all_nth_roots(a, 3);
+        assert_eq!(roots.len(), 3);
+        for root in &roots {
+            assert_eq!(root.exp_u64(3), a);
+        }
+        assert!(roots[0] != roots[1] && roots[0] != roots[2] && roots[1] != roots[2]);
+        assert!(roots.contains(&x));
+    }
+
+    #[test]
+    fn coprime_exponent_matches_unique_root_path() {
+        // 7 is coprime to p - 1 for Goldilocks, so this should hit the fast, unique-root path.
+        let x = F::rand();
+        let a = x.exp_u64(7);
+        let root = nth_root(a, 7).expect("a 7th power should have a 7th root");
+        assert_eq!(root.exp_u64(7), a);
+        assert_eq!(all_nth_roots(a, 7).len(), 1);
+    }
+
+    #[test]
+    fn square_roots_exercise_the_non_coprime_path() {
+        // Goldilocks: v_2(p - 1) = 32, so e = gcd(2, p - 1) = 2 and m = (p - 1) / 2 share the
+        // factor 2 -- the case a single `mod_inverse(e, m)` can't handle (it used to panic here).
+        let x = F::rand();
+        let a = x.square();
+        let root = nth_root(a, 2).expect("a square should have a square root");
+        assert_eq!(root.square(), a);
+        assert_eq!(all_nth_roots(a, 2).len(), 2);
+    }
+
+    #[test]
+    fn returns_none_when_no_square_root_exists() {
+        let mut a = F::rand();
+        while nth_root(a, 2).is_some() {
+            a = F::rand();
+        }
+        assert_eq!(nth_root(a, 2), None);
+    }
+
+    #[test]
+    fn repeated_prime_factor_in_the_exponent() {
+        // e = gcd(4, p - 1) = 4 = 2^2: the non-coprime prime (2) appears with multiplicity > 1,
+        // so `prime_root` is applied to it twice during e-th root extraction.
+        let x = F::rand();
+        let a = x.exp_u64(4);
+        let root = nth_root(a, 4).expect("a 4th power should have a 4th root");
+        assert_eq!(root.exp_u64(4), a);
+    }
+
+    #[test]
+    fn mixed_coprime_and_non_coprime_prime_factors() {
+        // e = gcd(6, p - 1) = 6 = 2 * 3: combines the non-coprime prime (2) and a coprime one (3)
+        // in the same e-th root extraction.
+        let x = F::rand();
+        let a = x.exp_u64(6);
+        let root = nth_root(a, 6).expect("a 6th power should have a 6th root");
+        assert_eq!(root.exp_u64(6), a);
+    }
+}